@@ -1,5 +1,6 @@
 #![recursion_limit = "192"]
 
+use std::collections::HashSet;
 use std::iter;
 use std::mem;
 use std::str::FromStr;
@@ -7,18 +8,27 @@ use std::str::FromStr;
 use proc_macro2::{Ident, Span, TokenStream};
 use proc_macro_error::{abort, proc_macro_error};
 use quote::{quote, ToTokens};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
 use syn::{
-    parse_macro_input, AttrStyle, Attribute, Data, DeriveInput, Expr, Fields, Index, Meta,
-    NestedMeta, Type, TypeGenerics, TypePath,
+    parse_macro_input, AttrStyle, Attribute, Data, DataStruct, DeriveInput, Expr, ExprLit, Field,
+    Fields, GenericArgument, Index, Lit, Meta, NestedMeta, PathArguments, Token, Type,
+    TypeGenerics, TypePath,
 };
 
-#[proc_macro_derive(H5Type)]
+/// Derives `H5Type` for `repr(C)`, `repr(packed)` and `repr(transparent)` structs, and for
+/// enums with an explicit integer representation.
+///
+/// The compound type of a packed struct has the packed field offsets, but dataset builders
+/// store compounds in the aligned C layout (`TypeDescriptor::to_c_repr()`) by default; to
+/// keep the packed layout in the file (e.g. for wire-format records), use `.packed(true)`.
+#[proc_macro_derive(H5Type, attributes(hdf5))]
 #[proc_macro_error]
 pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = input.ident;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
-    let body = impl_trait(&name, &input.data, &input.attrs, &ty_generics);
+    let (body, defaults) = impl_trait(&name, &input.data, &input.attrs, &ty_generics);
     let dummy = Ident::new(&format!("_IMPL_H5TYPE_FOR_{}", name), Span::call_site());
     let expanded = quote! {
         #[allow(dead_code, unused_variables, unused_attributes)]
@@ -31,25 +41,230 @@ pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                 fn type_descriptor() -> _h5::types::TypeDescriptor {
                     #body
                 }
+
+                #defaults
+            }
+        };
+    };
+    proc_macro::TokenStream::from(expanded)
+}
+
+#[proc_macro_derive(H5Attrs, attributes(hdf5))]
+#[proc_macro_error]
+pub fn derive_attrs(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let (write, read) = impl_attrs(&name, &input.data);
+    let dummy = Ident::new(&format!("_IMPL_H5ATTRS_FOR_{}", name), Span::call_site());
+    let expanded = quote! {
+        #[allow(dead_code, unused_variables, unused_attributes)]
+        const #dummy: () = {
+            extern crate hdf5 as _h5;
+
+            #[automatically_derived]
+            impl #impl_generics _h5::H5Attrs for #name #ty_generics #where_clause {
+                fn write_attrs(&self, loc: &_h5::Location) -> _h5::Result<()> {
+                    #write
+                }
+
+                fn read_attrs(loc: &_h5::Location) -> _h5::Result<Self> {
+                    #read
+                }
             }
         };
     };
     proc_macro::TokenStream::from(expanded)
 }
 
+fn impl_attrs(ty: &Ident, data: &Data) -> (TokenStream, TokenStream) {
+    let fields = named_fields(ty, data, "H5Attrs");
+    let names = pluck(fields.iter(), |f| {
+        parse_options(&f.attrs, &["rename"])
+            .rename
+            .unwrap_or_else(|| f.ident.as_ref().unwrap().to_string())
+    });
+    ensure_unique(&names, &fields, "attribute");
+    let idents = pluck(fields.iter(), |f| f.ident.clone().unwrap());
+    let (mut write, mut read) = (vec![], vec![]);
+    for ((ident, name), field) in idents.iter().zip(&names).zip(&fields) {
+        if let Some(ty) = option_inner(&field.ty) {
+            write.push(quote! {
+                _h5::write_attr_field::<#ty>(loc, #name, self.#ident.as_ref())?;
+            });
+            read.push(quote! {
+                #ident: _h5::read_attr_field::<#ty>(loc, #name, true)?
+            });
+        } else {
+            let ty = &field.ty;
+            write.push(quote! {
+                _h5::write_attr_field::<#ty>(loc, #name, Some(&self.#ident))?;
+            });
+            read.push(quote! {
+                #ident: _h5::read_attr_field::<#ty>(loc, #name, false)?.unwrap()
+            });
+        }
+    }
+    (quote! { #(#write)* Ok(()) }, quote! { Ok(Self { #(#read),* }) })
+}
+
+#[proc_macro_derive(H5Group, attributes(hdf5))]
+#[proc_macro_error]
+pub fn derive_group(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let (save, load) = impl_group(&name, &input.data);
+    let dummy = Ident::new(&format!("_IMPL_H5GROUP_FOR_{}", name), Span::call_site());
+    let expanded = quote! {
+        #[allow(dead_code, unused_variables, unused_attributes)]
+        const #dummy: () = {
+            extern crate hdf5 as _h5;
+
+            #[automatically_derived]
+            impl #impl_generics _h5::H5Group for #name #ty_generics #where_clause {
+                fn save(&self, parent: &_h5::Group, name: &str) -> _h5::Result<()> {
+                    let group = parent.create_group(name)?;
+                    #save
+                }
+
+                fn load(parent: &_h5::Group, name: &str) -> _h5::Result<Self> {
+                    let group = parent.group(name)?;
+                    #load
+                }
+            }
+        };
+    };
+    proc_macro::TokenStream::from(expanded)
+}
+
+fn impl_group(ty: &Ident, data: &Data) -> (TokenStream, TokenStream) {
+    let fields = named_fields(ty, data, "H5Group");
+    let options = pluck(fields.iter(), |f| {
+        parse_options(&f.attrs, &["rename", "chunk", "deflate", "group", "dataset"])
+    });
+    let names = fields
+        .iter()
+        .zip(&options)
+        .map(|(f, o)| o.rename.clone().unwrap_or_else(|| f.ident.as_ref().unwrap().to_string()))
+        .collect::<Vec<_>>();
+    ensure_unique(&names, &fields, "field");
+    let (mut save, mut load) = (vec![], vec![]);
+    for ((field, options), name) in fields.iter().zip(&options).zip(&names) {
+        let ident = field.ident.as_ref().unwrap();
+        let (ty, optional) = option_inner(&field.ty).map_or((&field.ty, false), |ty| (ty, true));
+        if options.group && options.dataset {
+            abort!(field, "`group` and `dataset` cannot be combined");
+        }
+        // arrays are recognized by the name of their type (which aliases hide)
+        let is_dataset = options.dataset || (!options.group && is_array(ty));
+        if !is_dataset && (options.chunk.is_some() || options.deflate.is_some()) {
+            abort!(field, "`chunk` and `deflate` can only be used on dataset fields");
+        }
+        let (store, fetch) = if options.group {
+            (
+                quote! { _h5::H5Group::save(value, &group, #name)?; },
+                quote! { <#ty as _h5::H5Group>::load(&group, #name)? },
+            )
+        } else if is_dataset {
+            let chunk = options.chunk.iter();
+            let deflate = options.deflate.iter();
+            (
+                quote! {
+                    group.new_dataset_builder()
+                        #(.chunk(#chunk))* #(.deflate(#deflate))*
+                        .with_data(value)
+                        .create(#name)?;
+                },
+                quote! { group.dataset(#name)?.read()? },
+            )
+        } else {
+            let read = if optional {
+                quote! { _h5::read_attr_field::<#ty>(&group, #name, true)? }
+            } else {
+                quote! { _h5::read_attr_field::<#ty>(&group, #name, false)?.unwrap() }
+            };
+            save.push(if optional {
+                quote! { _h5::write_attr_field::<#ty>(&group, #name, self.#ident.as_ref())?; }
+            } else {
+                quote! { _h5::write_attr_field::<#ty>(&group, #name, Some(&self.#ident))?; }
+            });
+            load.push(quote! { #ident: #read });
+            continue;
+        };
+        if optional {
+            save.push(quote! {
+                if let Some(value) = &self.#ident {
+                    #store
+                }
+            });
+            load.push(quote! {
+                #ident: if group.link_exists(#name) { Some(#fetch) } else { None }
+            });
+        } else {
+            save.push(quote! {
+                let value = &self.#ident;
+                #store
+            });
+            load.push(quote! { #ident: #fetch });
+        }
+    }
+    (quote! { #(#save)* Ok(()) }, quote! { Ok(Self { #(#load),* }) })
+}
+
+fn impl_defaults<F>(fields: &[F], names: &[String], options: &[FieldOptions]) -> TokenStream
+where
+    F: ToTokens,
+{
+    let (mut default_fields, mut default_names, mut values) = (vec![], vec![], vec![]);
+    for ((field, name), options) in fields.iter().zip(names).zip(options) {
+        if let Some(ref value) = options.default {
+            default_fields.push(field);
+            default_names.push(name);
+            values.push(value);
+        }
+    }
+    if default_fields.is_empty() {
+        return TokenStream::new();
+    }
+    // fields are written via raw pointers since the destination is (partially) uninitialized
+    // and may be a packed struct
+    quote! {
+        #[inline]
+        fn default_fields() -> &'static [&'static str] {
+            &[#(#default_names),*]
+        }
+
+        unsafe fn write_default_field(dst: *mut Self, name: &str) {
+            match name {
+                #(#default_names => ::std::ptr::write_unaligned(
+                    ::std::ptr::addr_of_mut!((*dst).#default_fields), #values
+                ),)*
+                _ => {}
+            }
+        }
+    }
+}
+
 fn impl_compound<F>(
     ty: &Ident, ty_generics: &TypeGenerics, fields: &[F], names: &[String], types: &[Type],
 ) -> TokenStream
 where
     F: ToTokens,
 {
+    // offsets are computed via raw pointers into uninitialized memory, so that no references
+    // to (potentially unaligned) fields of packed structs are ever created
     quote! {
-        let origin: *const #ty #ty_generics = ::std::ptr::null();
+        let uninit = ::std::mem::MaybeUninit::<#ty #ty_generics>::uninit();
+        let origin = uninit.as_ptr();
         let mut fields = vec![#(
             _h5::types::CompoundField {
                 name: #names.to_owned(),
                 ty: <#types as _h5::types::H5Type>::type_descriptor(),
-                offset: unsafe { &((*origin).#fields) as *const _ as _ },
+                offset: unsafe {
+                    (::std::ptr::addr_of!((*origin).#fields) as *const u8)
+                        .offset_from(origin as *const u8) as usize
+                },
                 index: 0,
             }
         ),*];
@@ -67,7 +282,7 @@ fn impl_transparent(ty: &Type) -> TokenStream {
     }
 }
 
-fn impl_enum(names: &[Ident], values: &[Expr], repr: &Ident) -> TokenStream {
+fn impl_enum(names: &[String], values: &[Expr], repr: &Ident) -> TokenStream {
     let size = Ident::new(
         &format!(
             "U{}",
@@ -84,7 +299,7 @@ fn impl_enum(names: &[Ident], values: &[Expr], repr: &Ident) -> TokenStream {
                 signed: #signed,
                 members: vec![#(
                     _h5::types::EnumMember {
-                        name: stringify!(#names).to_owned(),
+                        name: #names.to_owned(),
                         value: (#values) as #repr as _,
                     }
                 ),*],
@@ -102,6 +317,39 @@ fn is_phantom_data(ty: &Type) -> bool {
     }
 }
 
+fn named_fields<'a>(ty: &Ident, data: &'a Data, derive: &str) -> Vec<&'a Field> {
+    if let Data::Struct(DataStruct { fields: Fields::Named(ref fields), .. }) = *data {
+        return fields.named.iter().collect();
+    }
+    abort!(ty, "`{}` can only be derived for structs with named fields", derive)
+}
+
+fn is_array(ty: &Type) -> bool {
+    const ARRAYS: &[&str] =
+        &["Array", "ArrayD", "Array0", "Array1", "Array2", "Array3", "Array4", "Array5", "Array6"];
+    match *ty {
+        Type::Path(TypePath { qself: None, ref path }) => {
+            path.segments.iter().last().map_or(false, |x| ARRAYS.iter().any(|&a| x.ident == a))
+        }
+        _ => false,
+    }
+}
+
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let path = match *ty {
+        Type::Path(TypePath { qself: None, ref path }) => path,
+        _ => return None,
+    };
+    let segment = path.segments.iter().last().filter(|x| x.ident == "Option")?;
+    match segment.arguments {
+        PathArguments::AngleBracketed(ref args) if args.args.len() == 1 => match args.args[0] {
+            GenericArgument::Type(ref ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
 fn find_repr(attrs: &[Attribute], expected: &[&str]) -> Option<Ident> {
     for attr in attrs.iter() {
         if attr.style != AttrStyle::Outer {
@@ -117,6 +365,7 @@ fn find_repr(attrs: &[Attribute], expected: &[&str]) -> Option<Ident> {
         for item in list.nested.iter() {
             let path = match item {
                 NestedMeta::Meta(Meta::Path(ref path)) => path,
+                NestedMeta::Meta(Meta::List(ref list)) => &list.path, // e.g. packed(2)
                 _ => continue,
             };
             let ident = match path.get_ident() {
@@ -132,6 +381,89 @@ fn find_repr(attrs: &[Attribute], expected: &[&str]) -> Option<Ident> {
     None
 }
 
+/// A single `key` or `key = value` option inside `#[hdf5(...)]`.
+struct Hdf5Option {
+    key: Ident,
+    value: Option<Expr>,
+}
+
+impl Parse for Hdf5Option {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key = input.parse()?;
+        let value = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(Self { key, value })
+    }
+}
+
+impl ToTokens for Hdf5Option {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.key.to_tokens(tokens);
+        if let Some(ref value) = self.value {
+            quote!(= #value).to_tokens(tokens);
+        }
+    }
+}
+
+/// Options given via `#[hdf5(...)]` on a field or an enum variant.
+#[derive(Default)]
+struct FieldOptions {
+    rename: Option<String>,
+    default: Option<TokenStream>,
+    chunk: Option<Expr>,
+    deflate: Option<Expr>,
+    group: bool,
+    dataset: bool,
+}
+
+fn parse_options(attrs: &[Attribute], allowed: &[&str]) -> FieldOptions {
+    let mut options = FieldOptions::default();
+    for attr in attrs.iter() {
+        if attr.style != AttrStyle::Outer || !attr.path.is_ident("hdf5") {
+            continue;
+        }
+        let items = attr
+            .parse_args_with(Punctuated::<Hdf5Option, Token![,]>::parse_terminated)
+            .unwrap_or_else(|err| abort!(err.span(), "{}", err));
+        for item in items.iter() {
+            let key = item.key.to_string();
+            if !allowed.contains(&key.as_str()) {
+                abort!(item, "unknown `hdf5` attribute");
+            }
+            match (key.as_str(), &item.value) {
+                ("rename", Some(Expr::Lit(ExprLit { lit: Lit::Str(name), .. }))) => {
+                    options.rename = Some(name.value());
+                }
+                ("rename", _) => abort!(item, "expected `rename = \"...\"`"),
+                ("default", None) => {
+                    options.default = Some(quote!(::std::default::Default::default()));
+                }
+                ("default", Some(value)) => options.default = Some(value.to_token_stream()),
+                ("chunk", Some(value)) => options.chunk = Some(value.clone()),
+                ("deflate", Some(value)) => options.deflate = Some(value.clone()),
+                ("group", None) => options.group = true,
+                ("dataset", None) => options.dataset = true,
+                (_, Some(_)) => abort!(item, "expected `{}` without a value", key),
+                (_, None) => abort!(item, "expected `{} = ...`", key),
+            }
+        }
+    }
+    options
+}
+
+fn ensure_unique<T: ToTokens>(names: &[String], items: &[T], what: &str) {
+    let mut seen = HashSet::new();
+    for (name, item) in names.iter().zip(items) {
+        if !seen.insert(name) {
+            abort!(item, "duplicate {} name `{}`", what, name);
+        }
+    }
+}
+
 fn pluck<'a, I, F, T, S>(iter: I, func: F) -> Vec<S>
 where
     I: Iterator<Item = &'a T>,
@@ -143,7 +475,7 @@ where
 
 fn impl_trait(
     ty: &Ident, data: &Data, attrs: &[Attribute], ty_generics: &TypeGenerics,
-) -> TokenStream {
+) -> (TokenStream, TokenStream) {
     match *data {
         Data::Struct(ref data) => match data.fields {
             Fields::Unit => {
@@ -163,12 +495,28 @@ fn impl_trait(
                     });
                 if repr == "transparent" {
                     assert_eq!(fields.len(), 1);
-                    impl_transparent(&fields[0].ty)
+                    // the field is stored as is, so none of the options apply to it
+                    parse_options(&fields[0].attrs, &[]);
+                    (impl_transparent(&fields[0].ty), TokenStream::new())
                 } else {
                     let types = pluck(fields.iter(), |f| f.ty.clone());
+                    let options =
+                        pluck(fields.iter(), |f| parse_options(&f.attrs, &["rename", "default"]));
+                    let names = fields
+                        .iter()
+                        .zip(options.iter())
+                        .map(|(f, o)| {
+                            o.rename
+                                .clone()
+                                .unwrap_or_else(|| f.ident.as_ref().unwrap().to_string())
+                        })
+                        .collect::<Vec<_>>();
+                    ensure_unique(&names, &fields, "field");
                     let fields = pluck(fields.iter(), |f| f.ident.clone().unwrap());
-                    let names = fields.iter().map(ToString::to_string).collect::<Vec<_>>();
-                    impl_compound(ty, ty_generics, &fields, &names, &types)
+                    (
+                        impl_compound(ty, ty_generics, &fields, &names, &types),
+                        impl_defaults(&fields, &names, &options),
+                    )
                 }
             }
             Fields::Unnamed(ref fields) => {
@@ -189,11 +537,23 @@ fn impl_trait(
                     });
                 if repr == "transparent" {
                     assert_eq!(fields.len(), 1);
-                    impl_transparent(&fields[0].ty)
+                    // the field is stored as is, so none of the options apply to it
+                    parse_options(&fields[0].attrs, &[]);
+                    (impl_transparent(&fields[0].ty), TokenStream::new())
                 } else {
-                    let names = (0..fields.len()).map(|f| f.to_string()).collect::<Vec<_>>();
+                    let options =
+                        pluck(fields.iter(), |f| parse_options(&f.attrs, &["rename", "default"]));
+                    let names = options
+                        .iter()
+                        .enumerate()
+                        .map(|(i, o)| o.rename.clone().unwrap_or_else(|| i.to_string()))
+                        .collect::<Vec<_>>();
+                    ensure_unique(&names, &fields, "field");
                     let types = pluck(fields.iter(), |f| f.ty.clone());
-                    impl_compound(ty, ty_generics, &index, &names, &types)
+                    (
+                        impl_compound(ty, ty_generics, &index, &names, &types),
+                        impl_defaults(&index, &names, &options),
+                    )
                 }
             }
         },
@@ -213,9 +573,12 @@ fn impl_trait(
             let repr = find_repr(attrs, enum_reprs).unwrap_or_else(|| {
                 abort!(ty, "`H5Type` can only be derived for enums with explicit representation")
             });
-            let names = pluck(variants.iter(), |v| v.ident.clone());
+            let names = pluck(variants.iter(), |v| {
+                parse_options(&v.attrs, &["rename"]).rename.unwrap_or_else(|| v.ident.to_string())
+            });
+            ensure_unique(&names, &variants.iter().collect::<Vec<_>>(), "variant");
             let values = pluck(variants.iter(), |v| v.discriminant.clone().unwrap().1);
-            impl_enum(&names, &values, &repr)
+            (impl_enum(&names, &values, &repr), TokenStream::new())
         }
         Data::Union(_) => {
             abort!(ty, "cannot derive `H5Type` for tagged unions");
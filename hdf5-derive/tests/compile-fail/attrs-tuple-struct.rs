@@ -0,0 +1,7 @@
+extern crate hdf5_derive;
+use hdf5_derive::H5Attrs;
+
+#[derive(H5Attrs)]
+struct Foo(i64, f64);
+
+fn main() {}
@@ -0,0 +1,12 @@
+extern crate hdf5_derive;
+use hdf5_derive::H5Type;
+
+#[derive(H5Type)]
+#[repr(C)]
+struct Foo {
+    x: i64,
+    #[hdf5(rename = "x")]
+    y: i64,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+extern crate hdf5_derive;
+use hdf5_derive::H5Type;
+
+#[derive(H5Type)]
+#[repr(u8)]
+enum Foo {
+    #[hdf5(rename = "Y")]
+    X = 1,
+    Y = 2,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+extern crate hdf5_derive;
+use hdf5_derive::H5Group;
+
+#[derive(H5Group)]
+struct Foo {
+    #[hdf5(chunk = 10)]
+    x: f64,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+extern crate hdf5_derive;
+use hdf5_derive::H5Group;
+
+#[derive(H5Group)]
+struct Foo {
+    #[hdf5(group, dataset)]
+    x: Vec<f64>,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+extern crate hdf5_derive;
+use hdf5_derive::H5Type;
+
+#[derive(H5Type)]
+#[repr(transparent)]
+struct Foo(#[hdf5(rename = "y")] i64);
+
+fn main() {}
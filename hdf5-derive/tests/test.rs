@@ -1,6 +1,3 @@
-// due to compiler wrongfully complaining re: Copy impl missing for packed struct
-#![allow(unaligned_references)]
-
 #[macro_use]
 extern crate hdf5_derive;
 
@@ -44,6 +41,22 @@ struct P1 {
 #[repr(packed)]
 struct P2(i8, u32);
 
+#[derive(H5Type)]
+#[repr(C, packed)]
+struct P3 {
+    a: u8,
+    b: [u16; 3],
+    c: f64,
+}
+
+#[derive(H5Type)]
+#[repr(C, packed(2))]
+struct P4 {
+    a: u8,
+    b: u32,
+    c: u16,
+}
+
 #[derive(H5Type)]
 #[repr(transparent)]
 struct T1 {
@@ -76,6 +89,29 @@ fn test_compound_packed() {
             size: 5,
         })
     );
+    assert_eq!(
+        P3::type_descriptor(),
+        TD::Compound(CompoundType {
+            fields: vec![
+                CompoundField::typed::<u8>("a", 0, 0),
+                CompoundField::typed::<[u16; 3]>("b", 1, 1),
+                CompoundField::typed::<f64>("c", 7, 2),
+            ],
+            size: 15,
+        })
+    );
+    assert_eq!(P3::type_descriptor(), P3::type_descriptor().to_packed_repr());
+    assert_eq!(
+        P4::type_descriptor(),
+        TD::Compound(CompoundType {
+            fields: vec![
+                CompoundField::typed::<u8>("a", 0, 0),
+                CompoundField::typed::<u32>("b", 2, 1),
+                CompoundField::typed::<u16>("c", 6, 2),
+            ],
+            size: 8,
+        })
+    );
 }
 
 #[test]
@@ -106,10 +142,10 @@ fn test_compound_complex() {
         TD::Compound(CompoundType {
             fields: vec![
                 CompoundField::new("a", TD::FixedArray(Box::new(A::type_descriptor()), 4), 0, 0),
-                CompoundField::new("b", TD::FixedAscii(8), 64, 1),
+                CompoundField::new("b", TD::FixedAscii(8, StringPadding::NullPad), 64, 1),
                 CompoundField::new("c", TD::VarLenArray(Box::new(TD::Float(FloatSize::U8))), 72, 2),
                 CompoundField::new("d", TD::Boolean, 88, 3),
-                CompoundField::new("e", TD::FixedUnicode(7), 89, 4),
+                CompoundField::new("e", TD::FixedUnicode(7, StringPadding::NullPad), 89, 4),
                 CompoundField::new("f", TD::VarLenAscii, 96, 5),
                 CompoundField::new("g", TD::VarLenUnicode, 104, 6),
             ],
@@ -254,3 +290,72 @@ fn test_phantom_data() {
     assert_eq!(G3::<String>::type_descriptor(), C3::type_descriptor());
     assert_eq!(G4::<String>::type_descriptor(), C4::type_descriptor());
 }
+
+#[derive(H5Type)]
+#[repr(C)]
+struct Renamed {
+    #[hdf5(rename = "TimeStamp")]
+    time_stamp: i64,
+    #[hdf5(rename = "x.pos")]
+    x: f32,
+    y: f32,
+}
+
+#[derive(H5Type)]
+#[repr(C)]
+struct RenamedTuple(#[hdf5(rename = "first")] u8, u16);
+
+#[derive(H5Type, Clone, Copy)]
+#[repr(u8)]
+#[allow(dead_code)]
+enum RenamedEnum {
+    #[hdf5(rename = "RED")]
+    Red = 1,
+    Green = 2,
+}
+
+#[test]
+fn test_rename() {
+    let names = |td: TD| match td {
+        TD::Compound(c) => c.fields.into_iter().map(|f| f.name).collect::<Vec<_>>(),
+        TD::Enum(e) => e.members.into_iter().map(|m| m.name).collect::<Vec<_>>(),
+        _ => panic!(),
+    };
+    assert_eq!(names(Renamed::type_descriptor()), vec!["TimeStamp", "x.pos", "y"]);
+    assert_eq!(names(RenamedTuple::type_descriptor()), vec!["first", "1"]);
+    assert_eq!(names(RenamedEnum::type_descriptor()), vec!["RED", "Green"]);
+}
+
+#[derive(H5Type, Debug, PartialEq)]
+#[repr(C)]
+struct Defaults {
+    a: i32,
+    #[hdf5(default)]
+    b: u16,
+    #[hdf5(rename = "C", default = -1.5)]
+    c: f64,
+}
+
+#[derive(H5Type, Debug, PartialEq)]
+#[repr(C, packed)]
+struct DefaultsTuple(u8, #[hdf5(default = 7)] u32);
+
+#[test]
+fn test_defaults() {
+    assert_eq!(A::default_fields(), &[] as &[&str]);
+    assert_eq!(Defaults::default_fields(), &["b", "C"]);
+    assert_eq!(DefaultsTuple::default_fields(), &["1"]);
+
+    let mut value = Defaults { a: 1, b: 2, c: 3.0 };
+    unsafe {
+        Defaults::write_default_field(&mut value, "b");
+        Defaults::write_default_field(&mut value, "a");
+    }
+    assert_eq!(value, Defaults { a: 1, b: 0, c: 3.0 });
+    unsafe { Defaults::write_default_field(&mut value, "C") };
+    assert_eq!(value, Defaults { a: 1, b: 0, c: -1.5 });
+
+    let mut value = DefaultsTuple(1, 2);
+    unsafe { DefaultsTuple::write_default_field(&mut value, "1") };
+    assert_eq!(value, DefaultsTuple(1, 7));
+}
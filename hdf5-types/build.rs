@@ -1,6 +1,15 @@
+use std::env;
+
 fn main() {
     println!("cargo:rerun-if-changed=build.rs");
-    if std::env::var_os("DEP_HDF5_MSVC_DLL_INDIRECTION").is_some() {
-        println!("cargo:rustc-cfg=windows_dll");
+    for (key, _) in env::vars() {
+        match key.as_str() {
+            "DEP_HDF5_MSVC_DLL_INDIRECTION" => println!("cargo:rustc-cfg=windows_dll"),
+            key if key.starts_with("DEP_HDF5_VERSION_") => {
+                let version = key.trim_start_matches("DEP_HDF5_VERSION_").replace('_', ".");
+                println!("cargo:rustc-cfg=feature=\"{}\"", version);
+            }
+            _ => continue,
+        }
     }
 }
@@ -5,15 +5,21 @@ use std::ops::Deref;
 use std::ptr;
 use std::slice;
 
+/// A variable-length array (`hvl_t`) owning its elements.
+///
+/// The elements may themselves own heap memory (e.g. variable-length strings or nested
+/// variable-length arrays), which is reclaimed recursively when the array is dropped.
 #[repr(C)]
-pub struct VarLenArray<T: Copy> {
+pub struct VarLenArray<T> {
     len: usize,
     ptr: *const T,
     tag: PhantomData<T>,
 }
 
-impl<T: Copy> VarLenArray<T> {
-    pub unsafe fn from_parts(p: *const T, len: usize) -> Self {
+impl<T> VarLenArray<T> {
+    /// Moves `len` elements starting at `p` into a newly allocated array; the source
+    /// elements must not be used or dropped afterwards.
+    unsafe fn from_raw_moved(p: *const T, len: usize) -> Self {
         let (len, ptr) = if !p.is_null() && len != 0 {
             let dst = crate::malloc(len * mem::size_of::<T>());
             ptr::copy_nonoverlapping(p, dst.cast(), len);
@@ -24,11 +30,6 @@ impl<T: Copy> VarLenArray<T> {
         Self { len, ptr: ptr as *const _, tag: PhantomData }
     }
 
-    #[inline]
-    pub fn from_slice(arr: &[T]) -> Self {
-        unsafe { Self::from_parts(arr.as_ptr(), arr.len()) }
-    }
-
     #[inline]
     pub fn as_ptr(&self) -> *const T {
         self.ptr
@@ -50,10 +51,31 @@ impl<T: Copy> VarLenArray<T> {
     }
 }
 
-impl<T: Copy> Drop for VarLenArray<T> {
+impl<T: Clone> VarLenArray<T> {
+    pub unsafe fn from_parts(p: *const T, len: usize) -> Self {
+        let (len, ptr) = if !p.is_null() && len != 0 {
+            let dst = crate::malloc(len * mem::size_of::<T>()).cast::<T>();
+            for i in 0..len {
+                ptr::write(dst.add(i), (*p.add(i)).clone());
+            }
+            (len, dst)
+        } else {
+            (0, ptr::null_mut())
+        };
+        Self { len, ptr: ptr as *const _, tag: PhantomData }
+    }
+
+    #[inline]
+    pub fn from_slice(arr: &[T]) -> Self {
+        unsafe { Self::from_parts(arr.as_ptr(), arr.len()) }
+    }
+}
+
+impl<T> Drop for VarLenArray<T> {
     fn drop(&mut self) {
         if !self.ptr.is_null() {
             unsafe {
+                ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.ptr as *mut T, self.len));
                 crate::free(self.ptr as *mut _);
             }
             self.ptr = ptr::null();
@@ -64,14 +86,14 @@ impl<T: Copy> Drop for VarLenArray<T> {
     }
 }
 
-impl<T: Copy> Clone for VarLenArray<T> {
+impl<T: Clone> Clone for VarLenArray<T> {
     #[inline]
     fn clone(&self) -> Self {
         Self::from_slice(&*self)
     }
 }
 
-impl<T: Copy> Deref for VarLenArray<T> {
+impl<T> Deref for VarLenArray<T> {
     type Target = [T];
 
     #[inline]
@@ -84,58 +106,78 @@ impl<T: Copy> Deref for VarLenArray<T> {
     }
 }
 
-impl<'a, T: Copy> From<&'a [T]> for VarLenArray<T> {
+impl<'a, T: Clone> From<&'a [T]> for VarLenArray<T> {
     #[inline]
     fn from(arr: &[T]) -> Self {
         Self::from_slice(arr)
     }
 }
 
-impl<T: Copy> From<VarLenArray<T>> for Vec<T> {
+impl<T> From<Vec<T>> for VarLenArray<T> {
+    #[inline]
+    fn from(mut vec: Vec<T>) -> Self {
+        unsafe {
+            let arr = Self::from_raw_moved(vec.as_ptr(), vec.len());
+            vec.set_len(0);
+            arr
+        }
+    }
+}
+
+impl<T> From<VarLenArray<T>> for Vec<T> {
     #[inline]
     fn from(v: VarLenArray<T>) -> Self {
-        v.iter().copied().collect()
+        let mut v = mem::ManuallyDrop::new(v);
+        let vec = v.iter().map(|x| unsafe { ptr::read(x) }).collect();
+        if !v.ptr.is_null() {
+            unsafe {
+                crate::free(v.ptr as *mut _);
+            }
+            v.ptr = ptr::null();
+        }
+        vec
     }
 }
 
-impl<T: Copy, const N: usize> From<[T; N]> for VarLenArray<T> {
+impl<T, const N: usize> From<[T; N]> for VarLenArray<T> {
     #[inline]
     fn from(arr: [T; N]) -> Self {
-        unsafe { Self::from_parts(arr.as_ptr(), arr.len()) }
+        let arr = mem::ManuallyDrop::new(arr);
+        unsafe { Self::from_raw_moved(arr.as_ptr(), N) }
     }
 }
 
-impl<T: Copy> Default for VarLenArray<T> {
+impl<T> Default for VarLenArray<T> {
     #[inline]
     fn default() -> Self {
-        unsafe { Self::from_parts(ptr::null(), 0) }
+        unsafe { Self::from_raw_moved(ptr::null(), 0) }
     }
 }
 
-impl<T: Copy + PartialEq> PartialEq for VarLenArray<T> {
+impl<T: PartialEq> PartialEq for VarLenArray<T> {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
         self.as_slice() == other.as_slice()
     }
 }
 
-impl<T: Copy + Eq> Eq for VarLenArray<T> {}
+impl<T: Eq> Eq for VarLenArray<T> {}
 
-impl<T: Copy + PartialEq> PartialEq<[T]> for VarLenArray<T> {
+impl<T: PartialEq> PartialEq<[T]> for VarLenArray<T> {
     #[inline]
     fn eq(&self, other: &[T]) -> bool {
         self.as_slice() == other
     }
 }
 
-impl<T: Copy + PartialEq, const N: usize> PartialEq<[T; N]> for VarLenArray<T> {
+impl<T: PartialEq, const N: usize> PartialEq<[T; N]> for VarLenArray<T> {
     #[inline]
     fn eq(&self, other: &[T; N]) -> bool {
         self.as_slice() == other
     }
 }
 
-impl<T: Copy + fmt::Debug> fmt::Debug for VarLenArray<T> {
+impl<T: fmt::Debug> fmt::Debug for VarLenArray<T> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         self.as_slice().fmt(f)
@@ -150,7 +192,7 @@ pub mod tests {
 
     #[test]
     pub fn test_vla_empty_default() {
-        assert_eq!(&*S::default(), &[]);
+        assert_eq!(&*S::default(), &[] as &[u16]);
         assert!(S::default().is_empty());
         assert_eq!(S::default().len(), 0);
     }
@@ -181,4 +223,32 @@ pub mod tests {
         let v: Vec<_> = a.iter().cloned().collect();
         assert_eq!(v, vec![1, 2, 3]);
     }
+
+    #[test]
+    pub fn test_vla_nested() {
+        use std::rc::Rc;
+
+        let strings = vec!["foo".to_owned(), "bar".to_owned()];
+        let a = VarLenArray::from(strings.clone());
+        assert_eq!(a.as_slice(), strings.as_slice());
+        assert_eq!(a.clone(), a);
+        let v: Vec<String> = a.into();
+        assert_eq!(v, strings);
+
+        let nested = VarLenArray::from([VarLenArray::from([1.5_f32]), VarLenArray::default()]);
+        assert_eq!(format!("{:?}", nested), "[[1.5], []]");
+        assert_eq!(nested.clone(), nested);
+
+        // all elements must be dropped exactly once, including the ones in clones
+        let rc = Rc::new(());
+        let a = VarLenArray::from_slice(&[rc.clone(), rc.clone(), rc.clone()]);
+        let b = a.clone();
+        assert_eq!(Rc::strong_count(&rc), 7);
+        let v: Vec<_> = a.into();
+        assert_eq!(Rc::strong_count(&rc), 7);
+        drop(b);
+        assert_eq!(Rc::strong_count(&rc), 4);
+        drop(v);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
 }
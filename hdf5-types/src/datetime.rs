@@ -0,0 +1,370 @@
+use std::convert::TryFrom;
+use std::error::Error as StdError;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Units attribute value for datasets of [`Timestamp`] (following the CF conventions).
+pub const TIMESTAMP_UNITS: &str = "nanoseconds since 1970-01-01 00:00:00 UTC";
+
+/// Units attribute value for datasets of [`TimeDelta`] (following the CF conventions).
+pub const TIME_DELTA_UNITS: &str = "nanoseconds";
+
+/// A point in time, stored as a signed 64-bit integer number of nanoseconds since the
+/// Unix epoch (1970-01-01 00:00:00 UTC, not counting leap seconds).
+///
+/// The representable range is roughly from year 1677 to year 2262. With the `chrono` or
+/// `time` crate features enabled, timestamps can be converted to and from the respective
+/// date/time types.
+///
+/// The date/time types of those crates can't implement `H5Type` themselves, since the
+/// in-memory layout of an `H5Type` has to match its HDF5 datatype: neither
+/// `chrono::DateTime` nor `time::OffsetDateTime` is laid out as a single `i64` (they keep
+/// the date, the time of day and the offset in separate fields). Collections of them can be
+/// converted via [`try_from_slice()`](Self::try_from_slice) before writing and
+/// [`convert_slice()`](Self::convert_slice) after reading.
+#[repr(transparent)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(i64);
+
+/// A signed time interval, stored as a 64-bit integer number of nanoseconds.
+///
+/// With the `chrono` or `time` crate features enabled, it can be converted to and from the
+/// respective duration types (see [`Timestamp`] for converting collections of values).
+#[repr(transparent)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimeDelta(i64);
+
+/// Error returned when a time value does not fit into [`Timestamp`] or [`TimeDelta`] (or
+/// vice versa).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TimeRangeError;
+
+impl StdError for TimeRangeError {}
+
+impl fmt::Display for TimeRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "time value out of range")
+    }
+}
+
+impl Timestamp {
+    pub const UNIX_EPOCH: Self = Self(0);
+
+    #[inline]
+    pub const fn from_unix_nanos(nanos: i64) -> Self {
+        Self(nanos)
+    }
+
+    #[inline]
+    pub const fn unix_nanos(self) -> i64 {
+        self.0
+    }
+
+    /// Returns the time elapsed since the Unix epoch (negative for earlier timestamps).
+    #[inline]
+    pub const fn since_epoch(self) -> TimeDelta {
+        TimeDelta(self.0)
+    }
+
+    pub fn checked_add(self, delta: TimeDelta) -> Option<Self> {
+        self.0.checked_add(delta.0).map(Self)
+    }
+
+    pub fn checked_sub(self, delta: TimeDelta) -> Option<Self> {
+        self.0.checked_sub(delta.0).map(Self)
+    }
+
+    pub fn checked_duration_since(self, earlier: Self) -> Option<TimeDelta> {
+        self.0.checked_sub(earlier.0).map(TimeDelta)
+    }
+}
+
+impl TimeDelta {
+    pub const ZERO: Self = Self(0);
+
+    #[inline]
+    pub const fn from_nanos(nanos: i64) -> Self {
+        Self(nanos)
+    }
+
+    #[inline]
+    pub const fn nanos(self) -> i64 {
+        self.0
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.0.checked_add(other.0).map(Self)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.0.checked_sub(other.0).map(Self)
+    }
+}
+
+macro_rules! impl_slice_conversions {
+    ($ty:ident) => {
+        impl $ty {
+            /// Converts each of the values, failing if any of them is out of range.
+            pub fn try_from_slice<T>(values: &[T]) -> Result<Vec<Self>, TimeRangeError>
+            where
+                T: Clone,
+                Self: TryFrom<T, Error = TimeRangeError>,
+            {
+                values.iter().cloned().map(Self::try_from).collect()
+            }
+
+            /// Converts each of the values to `T`.
+            pub fn convert_slice<T: From<Self>>(values: &[Self]) -> Vec<T> {
+                values.iter().copied().map(T::from).collect()
+            }
+
+            /// Converts each of the values to `T`, failing if any of them is out of range.
+            pub fn try_convert_slice<T: TryFrom<Self>>(
+                values: &[Self],
+            ) -> Result<Vec<T>, T::Error> {
+                values.iter().copied().map(T::try_from).collect()
+            }
+        }
+    };
+}
+
+impl_slice_conversions!(Timestamp);
+impl_slice_conversions!(TimeDelta);
+
+impl fmt::Debug for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Timestamp({}ns)", self.0)
+    }
+}
+
+impl fmt::Debug for TimeDelta {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TimeDelta({}ns)", self.0)
+    }
+}
+
+fn duration_to_nanos(duration: Duration) -> Result<i64, TimeRangeError> {
+    i64::try_from(duration.as_nanos()).map_err(|_| TimeRangeError)
+}
+
+impl TryFrom<SystemTime> for Timestamp {
+    type Error = TimeRangeError;
+
+    fn try_from(time: SystemTime) -> Result<Self, TimeRangeError> {
+        match time.duration_since(UNIX_EPOCH) {
+            Ok(after) => duration_to_nanos(after).map(Self),
+            Err(err) => duration_to_nanos(err.duration()).map(|before| Self(-before)),
+        }
+    }
+}
+
+impl From<Timestamp> for SystemTime {
+    fn from(ts: Timestamp) -> Self {
+        let offset = Duration::from_nanos(ts.0.unsigned_abs());
+        if ts.0 >= 0 {
+            UNIX_EPOCH + offset
+        } else {
+            UNIX_EPOCH - offset
+        }
+    }
+}
+
+impl TryFrom<Duration> for TimeDelta {
+    type Error = TimeRangeError;
+
+    fn try_from(duration: Duration) -> Result<Self, TimeRangeError> {
+        duration_to_nanos(duration).map(Self)
+    }
+}
+
+impl TryFrom<TimeDelta> for Duration {
+    type Error = TimeRangeError;
+
+    fn try_from(delta: TimeDelta) -> Result<Self, TimeRangeError> {
+        u64::try_from(delta.0).map(Self::from_nanos).map_err(|_| TimeRangeError)
+    }
+}
+
+#[cfg(feature = "chrono")]
+mod chrono_impls {
+    use std::convert::TryFrom;
+
+    use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+
+    use super::{TimeDelta, TimeRangeError, Timestamp};
+
+    impl<Tz: TimeZone> TryFrom<DateTime<Tz>> for Timestamp {
+        type Error = TimeRangeError;
+
+        fn try_from(dt: DateTime<Tz>) -> Result<Self, TimeRangeError> {
+            dt.timestamp()
+                .checked_mul(1_000_000_000)
+                .and_then(|nanos| nanos.checked_add(i64::from(dt.timestamp_subsec_nanos())))
+                .map(Self)
+                .ok_or(TimeRangeError)
+        }
+    }
+
+    impl TryFrom<NaiveDateTime> for Timestamp {
+        type Error = TimeRangeError;
+
+        /// Interprets the naive date and time as UTC.
+        fn try_from(dt: NaiveDateTime) -> Result<Self, TimeRangeError> {
+            Self::try_from(Utc.from_utc_datetime(&dt))
+        }
+    }
+
+    impl From<Timestamp> for DateTime<Utc> {
+        fn from(ts: Timestamp) -> Self {
+            Utc.timestamp_nanos(ts.0)
+        }
+    }
+
+    impl From<Timestamp> for NaiveDateTime {
+        fn from(ts: Timestamp) -> Self {
+            DateTime::<Utc>::from(ts).naive_utc()
+        }
+    }
+
+    impl TryFrom<chrono::Duration> for TimeDelta {
+        type Error = TimeRangeError;
+
+        fn try_from(duration: chrono::Duration) -> Result<Self, TimeRangeError> {
+            duration.num_nanoseconds().map(Self).ok_or(TimeRangeError)
+        }
+    }
+
+    impl From<TimeDelta> for chrono::Duration {
+        fn from(delta: TimeDelta) -> Self {
+            Self::nanoseconds(delta.0)
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+mod time_impls {
+    use std::convert::TryFrom;
+
+    use time::OffsetDateTime;
+
+    use super::{TimeDelta, TimeRangeError, Timestamp};
+
+    impl TryFrom<OffsetDateTime> for Timestamp {
+        type Error = TimeRangeError;
+
+        fn try_from(dt: OffsetDateTime) -> Result<Self, TimeRangeError> {
+            i64::try_from(dt.unix_timestamp_nanos()).map(Self).map_err(|_| TimeRangeError)
+        }
+    }
+
+    impl From<Timestamp> for OffsetDateTime {
+        fn from(ts: Timestamp) -> Self {
+            // the range of `i64` nanoseconds is well within the supported range of years
+            Self::from_unix_timestamp_nanos(i128::from(ts.0)).unwrap()
+        }
+    }
+
+    impl TryFrom<time::Duration> for TimeDelta {
+        type Error = TimeRangeError;
+
+        fn try_from(duration: time::Duration) -> Result<Self, TimeRangeError> {
+            i64::try_from(duration.whole_nanoseconds()).map(Self).map_err(|_| TimeRangeError)
+        }
+    }
+
+    impl From<TimeDelta> for time::Duration {
+        fn from(delta: TimeDelta) -> Self {
+            Self::nanoseconds(delta.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use super::{TimeDelta, TimeRangeError, Timestamp};
+
+    #[test]
+    pub fn test_std_conversions() {
+        let ts = Timestamp::from_unix_nanos(1_600_000_000_123_456_789);
+        let st = SystemTime::from(ts);
+        assert_eq!(st.duration_since(UNIX_EPOCH).unwrap().as_nanos(), 1_600_000_000_123_456_789);
+        assert_eq!(Timestamp::try_from(st), Ok(ts));
+
+        let ts = Timestamp::from_unix_nanos(-1_500);
+        assert_eq!(Timestamp::try_from(SystemTime::from(ts)), Ok(ts));
+        assert_eq!(ts.since_epoch(), TimeDelta::from_nanos(-1_500));
+        assert_eq!(
+            Timestamp::UNIX_EPOCH.checked_duration_since(ts),
+            Some(TimeDelta::from_nanos(1_500))
+        );
+
+        let far = UNIX_EPOCH + Duration::from_secs(400 * 365 * 86_400);
+        assert_eq!(Timestamp::try_from(far), Err(TimeRangeError));
+
+        let delta = TimeDelta::try_from(Duration::from_millis(1_500)).unwrap();
+        assert_eq!(delta.nanos(), 1_500_000_000);
+        assert_eq!(Duration::try_from(delta), Ok(Duration::from_millis(1_500)));
+        assert_eq!(Duration::try_from(TimeDelta::from_nanos(-1)), Err(TimeRangeError));
+        assert_eq!(TimeDelta::try_from(Duration::from_secs(u64::MAX)), Err(TimeRangeError));
+    }
+
+    #[test]
+    pub fn test_slice_conversions() {
+        let times = [UNIX_EPOCH, UNIX_EPOCH + Duration::from_nanos(5)];
+        let ts = Timestamp::try_from_slice(&times).unwrap();
+        assert_eq!(ts, [Timestamp::UNIX_EPOCH, Timestamp::from_unix_nanos(5)]);
+        assert_eq!(Timestamp::convert_slice::<SystemTime>(&ts), times);
+        let far = UNIX_EPOCH + Duration::from_secs(400 * 365 * 86_400);
+        assert_eq!(Timestamp::try_from_slice(&[UNIX_EPOCH, far]), Err(TimeRangeError));
+
+        let deltas = [TimeDelta::from_nanos(3), TimeDelta::from_nanos(-3)];
+        assert_eq!(TimeDelta::try_convert_slice::<Duration>(&deltas), Err(TimeRangeError));
+        let durations = TimeDelta::try_convert_slice::<Duration>(&deltas[..1]).unwrap();
+        assert_eq!(durations, [Duration::from_nanos(3)]);
+        assert_eq!(TimeDelta::try_from_slice(&durations).unwrap(), &deltas[..1]);
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    pub fn test_chrono_conversions() {
+        use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let dt = Utc.from_utc_datetime(&date(2021, 3, 4).and_hms_nano_opt(5, 6, 7, 8).unwrap());
+        let ts = Timestamp::try_from(dt).unwrap();
+        assert_eq!(ts.unix_nanos(), 1_614_834_367_000_000_008);
+        assert_eq!(DateTime::<Utc>::from(ts), dt);
+        assert_eq!(Timestamp::try_from(dt.naive_utc()), Ok(ts));
+        let dts = Timestamp::convert_slice::<DateTime<Utc>>(&[ts, Timestamp::UNIX_EPOCH]);
+        assert_eq!(dts[0], dt);
+        assert_eq!(Timestamp::try_from_slice(&dts), Ok(vec![ts, Timestamp::UNIX_EPOCH]));
+        let before = date(1969, 12, 31).and_hms_milli_opt(23, 59, 59, 500).unwrap();
+        assert_eq!(Timestamp::try_from(before), Ok(Timestamp::from_unix_nanos(-500_000_000)));
+        let far = date(2300, 1, 1).and_hms_opt(0, 0, 0).unwrap();
+        assert_eq!(Timestamp::try_from(far), Err(TimeRangeError));
+
+        let delta = TimeDelta::try_from(chrono::Duration::milliseconds(-3)).unwrap();
+        assert_eq!(delta, TimeDelta::from_nanos(-3_000_000));
+        assert_eq!(chrono::Duration::from(delta), chrono::Duration::milliseconds(-3));
+    }
+
+    #[test]
+    #[cfg(feature = "time")]
+    pub fn test_time_conversions() {
+        use time::OffsetDateTime;
+
+        let dt = OffsetDateTime::from_unix_timestamp_nanos(-1_234_567_890).unwrap();
+        let ts = Timestamp::try_from(dt).unwrap();
+        assert_eq!(ts.unix_nanos(), -1_234_567_890);
+        assert_eq!(OffsetDateTime::from(ts), dt);
+        let far = OffsetDateTime::from_unix_timestamp(10_000_000_000).unwrap();
+        assert_eq!(Timestamp::try_from(far), Err(TimeRangeError));
+
+        let delta = TimeDelta::try_from(time::Duration::seconds(2)).unwrap();
+        assert_eq!(delta.nanos(), 2_000_000_000);
+        assert_eq!(time::Duration::from(delta), time::Duration::seconds(2));
+    }
+}
@@ -1,10 +1,21 @@
+use std::error::Error as StdError;
 use std::fmt::{self, Debug, Display};
 use std::mem;
 use std::ptr;
 use std::slice;
 
-use crate::h5type::{hvl_t, CompoundType, EnumType, FloatSize, H5Type, IntSize, TypeDescriptor};
-use crate::string::{VarLenAscii, VarLenUnicode};
+use ascii::AsciiStr;
+
+use crate::h5type::{
+    hvl_t, CompoundType, EnumType, FloatSize, H5Type, IntSize, Reference, StringPadding,
+    TypeDescriptor,
+};
+#[cfg(feature = "1.12.0")]
+use hdf5_sys::h5r::{H5R_ref_t, H5Rcopy, H5Rdestroy, H5Requal};
+
+use crate::opaque::fmt_bytes;
+use crate::references::{ObjectReference, RegionReference};
+use crate::string::{StringError, VarLenAscii, VarLenUnicode};
 
 fn read_raw<T: Copy>(buf: &[u8]) -> T {
     debug_assert_eq!(mem::size_of::<T>(), buf.len());
@@ -18,6 +29,27 @@ fn write_raw<T: Copy>(out: &mut [u8], value: T) {
     }
 }
 
+/// Widens the bits of an IEEE half-precision float to `f32` (every value is representable).
+#[cfg(not(feature = "half"))]
+fn f16_to_f32(bits: u16) -> f32 {
+    let sign = u32::from(bits >> 15) << 31;
+    let exp = u32::from((bits >> 10) & 0x1f);
+    let mantissa = u32::from(bits & 0x3ff);
+    match exp {
+        0 => {
+            // zero or subnormal: mantissa * 2^-24
+            let value = mantissa as f32 / (1 << 24) as f32;
+            if sign == 0 {
+                value
+            } else {
+                -value
+            }
+        }
+        0x1f => f32::from_bits(sign | 0x7f80_0000 | (mantissa << 13)),
+        _ => f32::from_bits(sign | ((exp + 127 - 15) << 23) | (mantissa << 13)),
+    }
+}
+
 unsafe trait DynDrop {
     fn dyn_drop(&mut self) {}
 }
@@ -117,6 +149,8 @@ impl From<DynInteger> for DynValue<'_> {
 #[derive(Copy, Clone, PartialEq)]
 pub enum DynScalar {
     Integer(DynInteger),
+    #[cfg(feature = "half")]
+    Float16(half::f16),
     Float32(f32),
     Float64(f64),
     Boolean(bool),
@@ -126,6 +160,8 @@ unsafe impl DynClone for DynScalar {
     fn dyn_clone(&mut self, out: &mut [u8]) {
         match self {
             Self::Integer(x) => x.dyn_clone(out),
+            #[cfg(feature = "half")]
+            Self::Float16(x) => write_raw(out, *x),
             Self::Float32(x) => write_raw(out, *x),
             Self::Float64(x) => write_raw(out, *x),
             Self::Boolean(x) => write_raw(out, *x),
@@ -137,6 +173,8 @@ impl Debug for DynScalar {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::Integer(x) => Debug::fmt(&x, f),
+            #[cfg(feature = "half")]
+            Self::Float16(x) => Debug::fmt(&x, f),
             Self::Float32(x) => Debug::fmt(&x, f),
             Self::Float64(x) => Debug::fmt(&x, f),
             Self::Boolean(x) => Debug::fmt(&x, f),
@@ -323,16 +361,42 @@ impl<'a> DynArray<'a> {
         }
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = DynValue> {
+    fn iter_elements(&self, tp: &'a TypeDescriptor, len: usize) -> impl Iterator<Item = DynValue> {
         let ptr = self.get_ptr();
-        let len = self.get_len();
-        let size = self.tp.size();
+        let size = tp.size();
         let buf = if !ptr.is_null() && len != 0 {
             unsafe { slice::from_raw_parts(ptr, len * size) }
         } else {
             [].as_ref()
         };
-        (0..len).map(move |i| DynValue::new(self.tp, &buf[(i * size)..((i + 1) * size)]))
+        (0..len).map(move |i| DynValue::new(tp, &buf[(i * size)..((i + 1) * size)]))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = DynValue> {
+        self.iter_elements(self.tp, self.get_len())
+    }
+
+    /// Returns the shape of the array, including dimensions of nested fixed-size arrays.
+    pub fn shape(&self) -> Vec<usize> {
+        let mut shape = vec![self.get_len()];
+        let mut tp = self.tp;
+        while let TypeDescriptor::FixedArray(ref inner, len) = *tp {
+            shape.push(len);
+            tp = inner;
+        }
+        shape
+    }
+
+    /// Iterates over the innermost elements in row-major order, flattening any nested
+    /// fixed-size arrays (e.g. a `[[f64; 3]; 3]` array yields 9 floats).
+    pub fn iter_flat(&self) -> impl Iterator<Item = DynValue> {
+        let mut tp = self.tp;
+        let mut len = self.get_len();
+        while let TypeDescriptor::FixedArray(ref inner, n) = *tp {
+            len *= n;
+            tp = inner;
+        }
+        self.iter_elements(tp, len)
     }
 }
 
@@ -417,20 +481,36 @@ impl<'a> From<DynArray<'a>> for DynValue<'a> {
 pub struct DynFixedString<'a> {
     buf: &'a [u8],
     unicode: bool,
+    padding: StringPadding,
 }
 
 impl<'a> DynFixedString<'a> {
-    pub fn new(buf: &'a [u8], unicode: bool) -> Self {
-        Self { buf, unicode }
+    pub fn new(buf: &'a [u8], unicode: bool, padding: StringPadding) -> Self {
+        Self { buf, unicode, padding }
+    }
+
+    pub fn padding(&self) -> StringPadding {
+        self.padding
     }
 
     pub fn raw_len(&self) -> usize {
-        self.buf.iter().rev().skip_while(|&c| *c == 0).count()
+        match self.padding {
+            StringPadding::NullTerm => {
+                self.buf.iter().position(|&c| c == 0).unwrap_or(self.buf.len())
+            }
+            StringPadding::NullPad => self.buf.iter().rev().skip_while(|&c| *c == 0).count(),
+            StringPadding::SpacePad => self.buf.iter().rev().skip_while(|&c| *c == b' ').count(),
+        }
     }
 
     pub fn get_buf(&self) -> &[u8] {
         &self.buf[..self.raw_len()]
     }
+
+    /// Returns the string contents (with padding removed), or `None` if not valid UTF-8.
+    pub fn as_str(&self) -> Option<&str> {
+        std::str::from_utf8(self.get_buf()).ok()
+    }
 }
 
 unsafe impl DynClone for DynFixedString<'_> {
@@ -499,6 +579,19 @@ impl<'a> DynVarLenString<'a> {
         }
     }
 
+    /// Returns the string contents, or `None` if not valid UTF-8.
+    ///
+    /// Null pointers (e.g. unwritten elements) are treated as empty strings.
+    pub fn as_str(&self) -> Option<&str> {
+        if self.get_ptr().is_null() {
+            Some("")
+        } else if self.unicode {
+            Some(self.as_unicode().as_str())
+        } else {
+            std::str::from_utf8(self.as_ascii().as_bytes()).ok()
+        }
+    }
+
     fn as_ascii(&self) -> &VarLenAscii {
         // Alignment is always at least usize for pointers from `hdf5-c`
         unsafe { &*(self.buf.as_ptr().cast::<VarLenAscii>()) }
@@ -583,6 +676,16 @@ pub enum DynString<'a> {
     VarLen(DynVarLenString<'a>),
 }
 
+impl DynString<'_> {
+    /// Returns the string contents, or `None` if not valid UTF-8.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Fixed(x) => x.as_str(),
+            Self::VarLen(x) => x.as_str(),
+        }
+    }
+}
+
 unsafe impl DynDrop for DynString<'_> {
     fn dyn_drop(&mut self) {
         if let DynString::VarLen(string) = self {
@@ -621,6 +724,204 @@ impl<'a> From<DynString<'a>> for DynValue<'a> {
     }
 }
 
+pub struct DynReference<'a> {
+    tp: Reference,
+    buf: &'a [u8],
+}
+
+impl<'a> DynReference<'a> {
+    pub fn new(tp: Reference, buf: &'a [u8]) -> Self {
+        Self { tp, buf }
+    }
+
+    pub fn reference_type(&self) -> Reference {
+        self.tp
+    }
+
+    pub fn as_object(&self) -> Option<ObjectReference> {
+        match self.tp {
+            Reference::Object => Some(read_raw(self.buf)),
+            _ => None,
+        }
+    }
+
+    pub fn as_region(&self) -> Option<RegionReference> {
+        match self.tp {
+            Reference::Region => Some(read_raw(self.buf)),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "1.12.0")]
+    fn as_std_ptr(&self) -> *const H5R_ref_t {
+        debug_assert_eq!(self.buf.len(), mem::size_of::<H5R_ref_t>());
+        self.buf.as_ptr().cast()
+    }
+}
+
+// Note: new-style references hold resources owned by the HDF5 library (e.g. an open file id),
+// so they have to be copied and released via `H5Rcopy` / `H5Rdestroy` rather than bytewise.
+
+unsafe impl DynDrop for DynReference<'_> {
+    fn dyn_drop(&mut self) {
+        #[cfg(feature = "1.12.0")]
+        if self.tp == Reference::Std {
+            unsafe {
+                H5Rdestroy(self.as_std_ptr() as *mut _);
+            }
+        }
+    }
+}
+
+unsafe impl DynClone for DynReference<'_> {
+    fn dyn_clone(&mut self, out: &mut [u8]) {
+        debug_assert_eq!(self.buf.len(), out.len());
+        #[cfg(feature = "1.12.0")]
+        if self.tp == Reference::Std {
+            unsafe {
+                H5Rcopy(self.as_std_ptr(), out.as_mut_ptr().cast());
+            }
+            return;
+        }
+        out.clone_from_slice(self.buf);
+    }
+}
+
+impl PartialEq for DynReference<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        #[cfg(feature = "1.12.0")]
+        if self.tp == Reference::Std && other.tp == Reference::Std {
+            return unsafe { H5Requal(self.as_std_ptr(), other.as_std_ptr()) > 0 };
+        }
+        self.tp == other.tp && self.buf == other.buf
+    }
+}
+
+impl Eq for DynReference<'_> {}
+
+impl Debug for DynReference<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.tp {
+            Reference::Object => Debug::fmt(&read_raw::<ObjectReference>(self.buf), f),
+            Reference::Region => Debug::fmt(&read_raw::<RegionReference>(self.buf), f),
+            #[cfg(feature = "1.12.0")]
+            Reference::Std => f.write_str("StdReference"),
+        }
+    }
+}
+
+impl Display for DynReference<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Debug::fmt(self, f)
+    }
+}
+
+impl<'a> From<DynReference<'a>> for DynValue<'a> {
+    fn from(value: DynReference<'a>) -> Self {
+        DynValue::Reference(value)
+    }
+}
+
+#[derive(PartialEq, Eq)]
+pub struct DynOpaque<'a> {
+    tag: &'a str,
+    buf: &'a [u8],
+}
+
+impl<'a> DynOpaque<'a> {
+    pub fn new(tag: &'a str, buf: &'a [u8]) -> Self {
+        Self { tag, buf }
+    }
+
+    pub fn tag(&self) -> &str {
+        self.tag
+    }
+
+    pub fn get_buf(&self) -> &[u8] {
+        self.buf
+    }
+}
+
+unsafe impl DynClone for DynOpaque<'_> {
+    fn dyn_clone(&mut self, out: &mut [u8]) {
+        debug_assert_eq!(self.buf.len(), out.len());
+        out.clone_from_slice(self.buf);
+    }
+}
+
+impl Debug for DynOpaque<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_bytes("Opaque", self.buf, f)
+    }
+}
+
+impl Display for DynOpaque<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Debug::fmt(self, f)
+    }
+}
+
+impl<'a> From<DynOpaque<'a>> for DynValue<'a> {
+    fn from(value: DynOpaque<'a>) -> Self {
+        DynValue::Opaque(value)
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct DynBitfield {
+    size: IntSize,
+    bits: u64,
+}
+
+impl DynBitfield {
+    pub fn read(buf: &[u8], size: IntSize) -> Self {
+        let bits = match size {
+            IntSize::U1 => read_raw::<u8>(buf) as _,
+            IntSize::U2 => read_raw::<u16>(buf) as _,
+            IntSize::U4 => read_raw::<u32>(buf) as _,
+            IntSize::U8 => read_raw::<u64>(buf),
+        };
+        Self { size, bits }
+    }
+
+    pub fn size(&self) -> IntSize {
+        self.size
+    }
+
+    pub fn bits(&self) -> u64 {
+        self.bits
+    }
+}
+
+unsafe impl DynClone for DynBitfield {
+    fn dyn_clone(&mut self, out: &mut [u8]) {
+        match self.size {
+            IntSize::U1 => write_raw(out, self.bits as u8),
+            IntSize::U2 => write_raw(out, self.bits as u16),
+            IntSize::U4 => write_raw(out, self.bits as u32),
+            IntSize::U8 => write_raw(out, self.bits),
+        }
+    }
+}
+
+impl Debug for DynBitfield {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:#0w$b}", self.bits, w = 2 + 8 * self.size as usize)
+    }
+}
+
+impl Display for DynBitfield {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Debug::fmt(self, f)
+    }
+}
+
+impl From<DynBitfield> for DynValue<'_> {
+    fn from(value: DynBitfield) -> Self {
+        DynValue::Bitfield(value)
+    }
+}
+
 #[derive(PartialEq)]
 pub enum DynValue<'a> {
     Scalar(DynScalar),
@@ -628,6 +929,9 @@ pub enum DynValue<'a> {
     Compound(DynCompound<'a>),
     Array(DynArray<'a>),
     String(DynString<'a>),
+    Reference(DynReference<'a>),
+    Opaque(DynOpaque<'a>),
+    Bitfield(DynBitfield),
 }
 
 impl<'a> DynValue<'a> {
@@ -636,7 +940,13 @@ impl<'a> DynValue<'a> {
         debug_assert_eq!(tp.size(), buf.len());
 
         match tp {
-            Integer(size) | Unsigned(size) => DynInteger::read(buf, true, *size).into(),
+            Integer(size) => DynInteger::read(buf, true, *size).into(),
+            Unsigned(size) => DynInteger::read(buf, false, *size).into(),
+            #[cfg(feature = "half")]
+            Float(FloatSize::U2) => DynScalar::Float16(read_raw(buf)).into(),
+            // without the `half` feature, half-precision floats are widened to `f32` (lossless)
+            #[cfg(not(feature = "half"))]
+            Float(FloatSize::U2) => DynScalar::Float32(f16_to_f32(read_raw(buf))).into(),
             Float(FloatSize::U4) => DynScalar::Float32(read_raw(buf)).into(),
             Float(FloatSize::U8) => DynScalar::Float64(read_raw(buf)).into(),
             Boolean => DynScalar::Boolean(read_raw(buf)).into(),
@@ -644,10 +954,13 @@ impl<'a> DynValue<'a> {
             Compound(ref tp) => DynCompound::new(tp, buf).into(),
             FixedArray(ref tp, n) => DynArray::new(tp, buf, Some(*n)).into(),
             VarLenArray(ref tp) => DynArray::new(tp, buf, None).into(),
-            FixedAscii(_) => DynFixedString::new(buf, false).into(),
-            FixedUnicode(_) => DynFixedString::new(buf, true).into(),
+            FixedAscii(_, padding) => DynFixedString::new(buf, false, *padding).into(),
+            FixedUnicode(_, padding) => DynFixedString::new(buf, true, *padding).into(),
             VarLenAscii => DynVarLenString::new(buf, false).into(),
             VarLenUnicode => DynVarLenString::new(buf, true).into(),
+            Reference(tp) => DynReference::new(*tp, buf).into(),
+            Opaque { ref tag, .. } => DynOpaque::new(tag, buf).into(),
+            Bitfield(size) => DynBitfield::read(buf, *size).into(),
         }
     }
 }
@@ -658,6 +971,7 @@ unsafe impl DynDrop for DynValue<'_> {
             Self::Compound(x) => x.dyn_drop(),
             Self::Array(x) => x.dyn_drop(),
             Self::String(x) => x.dyn_drop(),
+            Self::Reference(x) => x.dyn_drop(),
             _ => (),
         }
     }
@@ -671,6 +985,9 @@ unsafe impl DynClone for DynValue<'_> {
             Self::Compound(x) => x.dyn_clone(out),
             Self::Array(x) => x.dyn_clone(out),
             Self::String(x) => x.dyn_clone(out),
+            Self::Reference(x) => x.dyn_clone(out),
+            Self::Opaque(x) => x.dyn_clone(out),
+            Self::Bitfield(x) => x.dyn_clone(out),
         }
     }
 }
@@ -683,6 +1000,9 @@ impl Debug for DynValue<'_> {
             Self::Compound(x) => Debug::fmt(&x, f),
             Self::Array(x) => Debug::fmt(&x, f),
             Self::String(x) => Debug::fmt(&x, f),
+            Self::Reference(x) => Debug::fmt(&x, f),
+            Self::Opaque(x) => Debug::fmt(&x, f),
+            Self::Bitfield(x) => Debug::fmt(&x, f),
         }
     }
 }
@@ -795,6 +1115,398 @@ impl Display for OwnedDynValue {
     }
 }
 
+/// Error returned when assembling a dynamic value that doesn't fit its type descriptor.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DynValueError {
+    /// The value has a different type than the one expected.
+    TypeMismatch { expected: TypeDescriptor, actual: TypeDescriptor },
+    /// The type doesn't support this kind of value (e.g. a field of a non-compound type).
+    InvalidType(TypeDescriptor),
+    /// The compound type has no field with this name.
+    UnknownField(String),
+    /// The enum type has no member with this name.
+    UnknownEnumMember(String),
+    /// The number of array elements doesn't match the fixed array length.
+    LengthMismatch { expected: usize, actual: usize },
+    /// The array index is out of bounds.
+    IndexOutOfBounds { index: usize, len: usize },
+    /// The string cannot be stored in the string type.
+    StringError(StringError),
+}
+
+impl From<StringError> for DynValueError {
+    fn from(err: StringError) -> Self {
+        Self::StringError(err)
+    }
+}
+
+impl StdError for DynValueError {}
+
+impl Display for DynValueError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::TypeMismatch { expected, actual } => {
+                write!(f, "type mismatch: expected {}, got {}", expected, actual)
+            }
+            Self::InvalidType(tp) => write!(f, "invalid type for this kind of value: {}", tp),
+            Self::UnknownField(name) => write!(f, "unknown compound field: {:?}", name),
+            Self::UnknownEnumMember(name) => write!(f, "unknown enum member: {:?}", name),
+            Self::LengthMismatch { expected, actual } => {
+                write!(f, "array length mismatch: expected {}, got {}", expected, actual)
+            }
+            Self::IndexOutOfBounds { index, len } => {
+                write!(f, "array index out of bounds: {} >= {}", index, len)
+            }
+            Self::StringError(err) => Display::fmt(err, f),
+        }
+    }
+}
+
+/// Initializes a buffer with the "zero" value of the given type: numbers are zero,
+/// strings are empty and variable-length arrays have no elements.
+fn init_zeroed(tp: &TypeDescriptor, out: &mut [u8]) {
+    use TypeDescriptor::*;
+    debug_assert_eq!(tp.size(), out.len());
+    out.iter_mut().for_each(|c| *c = 0);
+    match tp {
+        VarLenAscii => unsafe {
+            ptr::write_unaligned(out.as_mut_ptr().cast(), crate::string::VarLenAscii::new());
+        },
+        VarLenUnicode => unsafe {
+            ptr::write_unaligned(out.as_mut_ptr().cast(), crate::string::VarLenUnicode::new());
+        },
+        Compound(ref compound) => {
+            for field in &compound.fields {
+                init_zeroed(&field.ty, &mut out[field.offset..(field.offset + field.ty.size())]);
+            }
+        }
+        FixedArray(ref tp, _) => {
+            let size = tp.size();
+            for chunk in out.chunks_exact_mut(size.max(1)) {
+                init_zeroed(tp, chunk);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_string(tp: &TypeDescriptor) -> bool {
+    use TypeDescriptor::*;
+    matches!(tp, FixedAscii(..) | FixedUnicode(..) | VarLenAscii | VarLenUnicode)
+}
+
+/// Moves `value` into `out`, releasing whatever `out` previously held.
+///
+/// Strings are converted between string types; otherwise the types must match exactly.
+fn put_value(
+    tp: &TypeDescriptor, out: &mut [u8], mut value: OwnedDynValue,
+) -> Result<(), DynValueError> {
+    if value.tp != *tp {
+        let converted = match value.get() {
+            DynValue::String(ref s) if is_string(tp) && s.as_str().is_some() => {
+                OwnedDynValue::from_str_as(tp.clone(), s.as_str().unwrap_or_default())?
+            }
+            _ => {
+                return Err(DynValueError::TypeMismatch {
+                    expected: tp.clone(),
+                    actual: value.tp.clone(),
+                })
+            }
+        };
+        return put_value(tp, out, converted);
+    }
+    DynValue::new(tp, out).dyn_drop();
+    out.copy_from_slice(&value.buf);
+    // ownership of any nested allocations has been moved to `out`
+    value.tp = <[u8; 0]>::type_descriptor();
+    value.buf = Box::new([]);
+    Ok(())
+}
+
+impl OwnedDynValue {
+    /// Creates the "zero" value of the given type.
+    ///
+    /// Numbers are set to zero, strings are empty and variable-length arrays have no
+    /// elements; fields and elements can then be filled in via [`DynValueBuilder`].
+    pub fn zeroed(tp: TypeDescriptor) -> Self {
+        let mut buf = vec![0; tp.size()].into_boxed_slice();
+        init_zeroed(&tp, &mut buf);
+        Self { tp, buf }
+    }
+
+    /// Creates a string value of the given string type.
+    pub fn from_str_as(tp: TypeDescriptor, s: &str) -> Result<Self, DynValueError> {
+        use TypeDescriptor::*;
+        let mut value = Self::zeroed(tp);
+        match value.tp {
+            FixedAscii(len, padding) | FixedUnicode(len, padding) => {
+                if matches!(value.tp, FixedAscii(..)) {
+                    AsciiStr::from_ascii(s).map_err(StringError::from)?;
+                }
+                if s.len() > len {
+                    return Err(StringError::InsufficientCapacity.into());
+                }
+                if padding != StringPadding::SpacePad && s.as_bytes().contains(&0) {
+                    return Err(StringError::InternalNull.into());
+                }
+                let pad = if padding == StringPadding::SpacePad { b' ' } else { 0 };
+                value.buf[..s.len()].copy_from_slice(s.as_bytes());
+                value.buf[s.len()..].iter_mut().for_each(|c| *c = pad);
+            }
+            VarLenAscii => {
+                let s = crate::string::VarLenAscii::from_ascii(s)?;
+                put_value(&VarLenAscii, &mut value.buf, s.into())?;
+            }
+            VarLenUnicode => {
+                let s = s.parse::<crate::string::VarLenUnicode>()?;
+                put_value(&VarLenUnicode, &mut value.buf, s.into())?;
+            }
+            _ => return Err(DynValueError::InvalidType(value.tp.clone())),
+        }
+        Ok(value)
+    }
+
+    /// Creates a value of the given enum type from the name of one of its members.
+    pub fn from_enum_member(tp: TypeDescriptor, name: &str) -> Result<Self, DynValueError> {
+        let (size, value) = match tp {
+            TypeDescriptor::Enum(ref enum_type) => enum_type
+                .members
+                .iter()
+                .find(|member| member.name == name)
+                .map(|member| (enum_type.size, member.value))
+                .ok_or_else(|| DynValueError::UnknownEnumMember(name.into()))?,
+            _ => return Err(DynValueError::InvalidType(tp)),
+        };
+        let mut buf = vec![0; tp.size()].into_boxed_slice();
+        match size {
+            IntSize::U1 => write_raw(&mut buf, value as u8),
+            IntSize::U2 => write_raw(&mut buf, value as u16),
+            IntSize::U4 => write_raw(&mut buf, value as u32),
+            IntSize::U8 => write_raw(&mut buf, value),
+        }
+        Ok(Self { tp, buf })
+    }
+
+    /// Creates a fixed-size or variable-length array value from its elements.
+    pub fn from_elements(
+        tp: TypeDescriptor, elements: Vec<OwnedDynValue>,
+    ) -> Result<Self, DynValueError> {
+        let mut value = Self::zeroed(tp);
+        match value.tp {
+            TypeDescriptor::FixedArray(ref elem_tp, len) => {
+                if elements.len() != len {
+                    return Err(DynValueError::LengthMismatch {
+                        expected: len,
+                        actual: elements.len(),
+                    });
+                }
+                let size = elem_tp.size();
+                for (i, element) in elements.into_iter().enumerate() {
+                    put_value(elem_tp, &mut value.buf[(i * size)..((i + 1) * size)], element)?;
+                }
+            }
+            TypeDescriptor::VarLenArray(ref elem_tp) => {
+                if elements.is_empty() {
+                    return Ok(value);
+                }
+                let (len, size) = (elements.len(), elem_tp.size());
+                let data = unsafe {
+                    let ptr = crate::malloc(len * size).cast::<u8>();
+                    write_raw(&mut value.buf, hvl_t { len, ptr: ptr.cast() });
+                    slice::from_raw_parts_mut(ptr, len * size)
+                };
+                // initialize all elements first, so that the array can be dropped on error
+                for chunk in data.chunks_exact_mut(size.max(1)) {
+                    init_zeroed(elem_tp, chunk);
+                }
+                for (i, element) in elements.into_iter().enumerate() {
+                    put_value(elem_tp, &mut data[(i * size)..((i + 1) * size)], element)?;
+                }
+            }
+            _ => return Err(DynValueError::InvalidType(value.tp.clone())),
+        }
+        Ok(value)
+    }
+}
+
+/// Builder for compound and fixed-size array values whose type is only known at runtime.
+///
+/// The builder starts from the [zero value](OwnedDynValue::zeroed) of the type, so any
+/// fields or elements that are not set explicitly are zero or empty.
+#[derive(Clone, Debug)]
+pub struct DynValueBuilder {
+    value: OwnedDynValue,
+}
+
+impl DynValueBuilder {
+    /// Creates a builder for a value of the given type.
+    pub fn new(tp: TypeDescriptor) -> Self {
+        Self { value: OwnedDynValue::zeroed(tp) }
+    }
+
+    /// Sets a field of a compound value.
+    ///
+    /// The value must have the same type as the field, except for strings which are
+    /// converted to the string type of the field.
+    pub fn field<V>(mut self, name: &str, value: V) -> Result<Self, DynValueError>
+    where
+        V: Into<OwnedDynValue>,
+    {
+        let field = match self.value.tp {
+            TypeDescriptor::Compound(ref compound) => compound
+                .fields
+                .iter()
+                .find(|field| field.name == name)
+                .cloned()
+                .ok_or_else(|| DynValueError::UnknownField(name.into()))?,
+            _ => return Err(DynValueError::InvalidType(self.value.tp.clone())),
+        };
+        let out = &mut self.value.buf[field.offset..(field.offset + field.ty.size())];
+        put_value(&field.ty, out, value.into())?;
+        Ok(self)
+    }
+
+    /// Sets an element of a fixed-size array value.
+    pub fn element<V>(mut self, index: usize, value: V) -> Result<Self, DynValueError>
+    where
+        V: Into<OwnedDynValue>,
+    {
+        let (elem_tp, len) = match self.value.tp {
+            TypeDescriptor::FixedArray(ref tp, len) => ((**tp).clone(), len),
+            _ => return Err(DynValueError::InvalidType(self.value.tp.clone())),
+        };
+        if index >= len {
+            return Err(DynValueError::IndexOutOfBounds { index, len });
+        }
+        let size = elem_tp.size();
+        put_value(
+            &elem_tp,
+            &mut self.value.buf[(index * size)..((index + 1) * size)],
+            value.into(),
+        )?;
+        Ok(self)
+    }
+
+    /// Returns the assembled value.
+    pub fn finish(self) -> OwnedDynValue {
+        self.value
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serialize {
+    use serde::ser::{Error, Serialize, SerializeMap, SerializeSeq, Serializer};
+
+    use super::*;
+
+    impl Serialize for DynInteger {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match *self {
+                Self::Int8(x) => serializer.serialize_i8(x),
+                Self::Int16(x) => serializer.serialize_i16(x),
+                Self::Int32(x) => serializer.serialize_i32(x),
+                Self::Int64(x) => serializer.serialize_i64(x),
+                Self::UInt8(x) => serializer.serialize_u8(x),
+                Self::UInt16(x) => serializer.serialize_u16(x),
+                Self::UInt32(x) => serializer.serialize_u32(x),
+                Self::UInt64(x) => serializer.serialize_u64(x),
+            }
+        }
+    }
+
+    impl Serialize for DynScalar {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match *self {
+                Self::Integer(ref x) => x.serialize(serializer),
+                #[cfg(feature = "half")]
+                Self::Float16(x) => serializer.serialize_f32(x.to_f32()),
+                Self::Float32(x) => serializer.serialize_f32(x),
+                Self::Float64(x) => serializer.serialize_f64(x),
+                Self::Boolean(x) => serializer.serialize_bool(x),
+            }
+        }
+    }
+
+    impl Serialize for DynEnum<'_> {
+        /// Enum values are serialized as member names; values that don't match any member
+        /// fall back to the underlying integer.
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match self.name() {
+                Some(name) => serializer.serialize_str(name),
+                None => self.value.serialize(serializer),
+            }
+        }
+    }
+
+    impl Serialize for DynCompound<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut map = serializer.serialize_map(Some(self.tp.fields.len()))?;
+            for (name, value) in self.iter() {
+                map.serialize_entry(name, &value)?;
+            }
+            map.end()
+        }
+    }
+
+    impl Serialize for DynArray<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut seq = serializer.serialize_seq(Some(self.get_len()))?;
+            for value in self.iter() {
+                seq.serialize_element(&value)?;
+            }
+            seq.end()
+        }
+    }
+
+    impl Serialize for DynString<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match self.as_str() {
+                Some(s) => serializer.serialize_str(s),
+                None => Err(S::Error::custom("string is not valid UTF-8")),
+            }
+        }
+    }
+
+    impl Serialize for DynReference<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_str(self)
+        }
+    }
+
+    impl Serialize for DynOpaque<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(self.get_buf())
+        }
+    }
+
+    impl Serialize for DynBitfield {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_u64(self.bits())
+        }
+    }
+
+    impl Serialize for DynValue<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match self {
+                Self::Scalar(x) => x.serialize(serializer),
+                Self::Enum(x) => x.serialize(serializer),
+                Self::Compound(x) => x.serialize(serializer),
+                Self::Array(x) => x.serialize(serializer),
+                Self::String(x) => x.serialize(serializer),
+                Self::Reference(x) => x.serialize(serializer),
+                Self::Opaque(x) => x.serialize(serializer),
+                Self::Bitfield(x) => x.serialize(serializer),
+            }
+        }
+    }
+
+    impl Serialize for OwnedDynValue {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            self.get().serialize(serializer)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
@@ -854,8 +1566,12 @@ mod tests {
         })
     }
 
+    fn td_point_coords() -> TD {
+        TD::FixedArray(Box::new(TD::Float(FloatSize::U4)), 2)
+    }
+
     fn td_point() -> TD {
-        let coords = TD::FixedArray(Box::new(TD::Float(FloatSize::U4)), 2);
+        let coords = td_point_coords();
         TD::Compound(CompoundType {
             fields: Vec::from(
                 [
@@ -875,8 +1591,8 @@ mod tests {
             fields: Vec::from(
                 [
                     CompoundField::new("points", points, 0, 0),
-                    CompoundField::new("fa", TD::FixedAscii(5), 16, 1),
-                    CompoundField::new("fu", TD::FixedUnicode(5), 21, 2),
+                    CompoundField::new("fa", TD::FixedAscii(5, StringPadding::NullPad), 16, 1),
+                    CompoundField::new("fu", TD::FixedUnicode(5, StringPadding::NullPad), 21, 2),
                     CompoundField::new("va", TD::VarLenAscii, 32, 3),
                     CompoundField::new("vu", TD::VarLenUnicode, 40, 4),
                 ]
@@ -969,6 +1685,12 @@ mod tests {
         }
     }
 
+    unsafe impl crate::h5type::H5Type for Data {
+        fn type_descriptor() -> TypeDescriptor {
+            td_data()
+        }
+    }
+
     unsafe impl crate::h5type::H5Type for BigStruct {
         fn type_descriptor() -> TypeDescriptor {
             td_big_struct()
@@ -982,6 +1704,17 @@ mod tests {
         assert_eq!(OwnedDynValue::from(s.clone()), OwnedDynValue::new(s.clone()));
     }
 
+    #[test]
+    fn test_dyn_value_unsigned() {
+        let value = OwnedDynValue::new(u16::MAX);
+        match value.get() {
+            DynValue::Scalar(DynScalar::Integer(x)) => assert_eq!(x, DynInteger::UInt16(u16::MAX)),
+            _ => panic!("expected an integer value"),
+        }
+        assert_eq!(format!("{}", value.get()), "65535");
+        assert_ne!(value, OwnedDynValue::new(-1i16));
+    }
+
     #[test]
     fn test_dyn_value_clone_drop() {
         let val1 = OwnedDynValue::new(big_struct_1());
@@ -999,6 +1732,225 @@ mod tests {
         assert_ne!(val2, val1);
     }
 
+    #[test]
+    fn test_dyn_array_nested() {
+        let matrix = OwnedDynValue::new([[1.0_f64, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        let arr = match matrix.get() {
+            DynValue::Array(arr) => arr,
+            _ => panic!("expected an array value"),
+        };
+        assert_eq!(arr.shape(), vec![2, 3]);
+        assert_eq!(arr.iter().count(), 2);
+        let rows: Vec<_> = arr.iter().map(|row| format!("{}", row)).collect();
+        assert_eq!(rows, vec!["[1.0, 2.0, 3.0]", "[4.0, 5.0, 6.0]"]);
+        let flat: Vec<_> = arr.iter_flat().map(|x| format!("{}", x)).collect();
+        assert_eq!(flat, vec!["1.0", "2.0", "3.0", "4.0", "5.0", "6.0"]);
+
+        let vla = OwnedDynValue::new(VarLenArray::from_slice(&[[1_u8, 2], [3, 4], [5, 6]]));
+        match vla.get() {
+            DynValue::Array(arr) => {
+                assert_eq!(arr.shape(), vec![3, 2]);
+                assert_eq!(arr.iter_flat().count(), 6);
+            }
+            _ => panic!("expected an array value"),
+        }
+    }
+
+    #[test]
+    fn test_dyn_value_opaque_bitfield() {
+        use crate::opaque::{Bitfield, Opaque};
+
+        let blob = OwnedDynValue::new(Opaque::new([0xca, 0xfe, 0x01]));
+        match blob.get() {
+            DynValue::Opaque(x) => {
+                assert_eq!(x.tag(), "");
+                assert_eq!(x.get_buf(), &[0xca, 0xfe, 0x01]);
+            }
+            _ => panic!("expected an opaque value"),
+        }
+        assert_eq!(format!("{}", blob.get()), "Opaque(0xcafe01)");
+        assert_eq!(blob.clone(), blob);
+        assert_eq!(blob.cast::<Opaque<3>>().unwrap().into_inner(), [0xca, 0xfe, 0x01]);
+
+        let tp = TD::Opaque { size: 2, tag: "vendor".into() };
+        let buf = [0xab, 0xcd];
+        match DynValue::new(&tp, &buf) {
+            DynValue::Opaque(x) => assert_eq!(x.tag(), "vendor"),
+            _ => panic!("expected an opaque value"),
+        }
+
+        #[cfg(not(feature = "half"))]
+        for &(bits, value) in &[
+            (0x3e00_u16, 1.5_f32),
+            (0xc000, -2.),
+            (0x7bff, 65504.),
+            (0x0001, 5.960_464_5e-8),
+            (0x8000, -0.),
+            (0x7c00, f32::INFINITY),
+        ] {
+            let buf = bits.to_ne_bytes();
+            match DynValue::new(&TD::Float(FloatSize::U2), &buf) {
+                DynValue::Scalar(DynScalar::Float32(x)) => {
+                    assert_eq!(x.to_bits(), value.to_bits(), "{:#06x}", bits)
+                }
+                _ => panic!("expected a float value"),
+            }
+        }
+        #[cfg(not(feature = "half"))]
+        assert!(f16_to_f32(0x7e00).is_nan());
+
+        let flags = OwnedDynValue::new(Bitfield(0b1010_u16));
+        match flags.get() {
+            DynValue::Bitfield(x) => {
+                assert_eq!(x.size(), IntSize::U2);
+                assert_eq!(x.bits(), 0b1010);
+            }
+            _ => panic!("expected a bitfield value"),
+        }
+        assert_eq!(format!("{}", flags.get()), "0b0000000000001010");
+        assert_eq!(flags.clone(), flags);
+        assert_ne!(flags, OwnedDynValue::new(Bitfield(0b1010_u32)));
+    }
+
+    #[test]
+    fn test_dyn_fixed_string_padding() {
+        let buf = b"ab c  \0x";
+        let check = |padding, expected: &str| {
+            let tp = TD::FixedAscii(buf.len(), padding);
+            match DynValue::new(&tp, buf) {
+                DynValue::String(DynString::Fixed(s)) => {
+                    assert_eq!(s.padding(), padding);
+                    assert_eq!(s.get_buf(), expected.as_bytes());
+                }
+                _ => panic!("expected a fixed-length string"),
+            }
+        };
+        check(StringPadding::NullTerm, "ab c  ");
+        check(StringPadding::NullPad, "ab c  \0x");
+        check(StringPadding::SpacePad, "ab c  \0x");
+
+        let buf = b"ab c  ";
+        let tp = TD::FixedAscii(buf.len(), StringPadding::SpacePad);
+        assert_eq!(format!("{}", DynValue::new(&tp, buf)), "\"ab c\"");
+        let tp = TD::FixedAscii(buf.len(), StringPadding::NullPad);
+        assert_eq!(format!("{}", DynValue::new(&tp, buf)), "\"ab c  \"");
+    }
+
+    #[test]
+    fn test_dyn_string_as_str() {
+        let as_str = |value: &OwnedDynValue| match value.get() {
+            DynValue::String(s) => s.as_str().map(ToOwned::to_owned),
+            _ => panic!("expected a string"),
+        };
+        let fixed = OwnedDynValue::new(FixedUnicode::<8>::from_str("éé").unwrap());
+        assert_eq!(as_str(&fixed).unwrap(), "éé");
+        let varlen = OwnedDynValue::new(VarLenAscii::from_ascii("foo").unwrap());
+        assert_eq!(as_str(&varlen).unwrap(), "foo");
+        let varlen = OwnedDynValue::new(VarLenUnicode::from_str("ö").unwrap());
+        assert_eq!(as_str(&varlen).unwrap(), "ö");
+
+        let tp = TD::FixedAscii(3, StringPadding::NullPad);
+        match DynValue::new(&tp, &[b'a', 0xff, 0]) {
+            DynValue::String(s) => assert_eq!(s.as_str(), None),
+            _ => panic!("expected a string"),
+        }
+        let null = 0_usize;
+        let buf = unsafe {
+            std::slice::from_raw_parts(
+                (&null as *const usize).cast::<u8>(),
+                mem::size_of::<usize>(),
+            )
+        };
+        match DynValue::new(&TD::VarLenUnicode, buf) {
+            DynValue::String(s) => assert_eq!(s.as_str(), Some("")),
+            _ => panic!("expected a string"),
+        }
+    }
+
+    #[test]
+    fn test_dyn_value_builder() {
+        let point = |x: f32, color: &str, nice: bool| {
+            let coords = vec![OwnedDynValue::new(x), OwnedDynValue::new(-x)];
+            let coords = OwnedDynValue::from_elements(td_point_coords(), coords).unwrap();
+            let color = OwnedDynValue::from_enum_member(td_color(), color).unwrap();
+            DynValueBuilder::new(td_point())
+                .field("coords", coords)
+                .and_then(|b| b.field("color", color))
+                .and_then(|b| b.field("nice", nice))
+                .unwrap()
+                .finish()
+        };
+        let points = vec![point(1.0, "Red", true), point(2.0, "Blue", false)];
+        let points =
+            OwnedDynValue::from_elements(TD::VarLenArray(Box::new(td_point())), points).unwrap();
+        let value = DynValueBuilder::new(td_data())
+            .field("points", points)
+            .and_then(|b| b.field("fa", VarLenUnicode::from_str("abc").unwrap()))
+            .and_then(|b| b.field("fu", FixedAscii::<3>::from_ascii("xyz").unwrap()))
+            .and_then(|b| b.field("va", VarLenAscii::from_ascii("wat").unwrap()))
+            .unwrap()
+            .finish();
+
+        let expected = Data {
+            points: VarLenArray::from_slice(&[
+                Point { coords: [1.0, -1.0], color: Color::Red, nice: true },
+                Point { coords: [2.0, -2.0], color: Color::Blue, nice: false },
+            ]),
+            fa: FixedAscii::from_ascii("abc").unwrap(),
+            fu: FixedUnicode::from_str("xyz").unwrap(),
+            va: VarLenAscii::from_ascii("wat").unwrap(),
+            vu: VarLenUnicode::new(),
+        };
+        assert_eq!(value, OwnedDynValue::new(expected.clone()));
+        assert_eq!(value.clone().cast::<Data>().unwrap(), expected);
+
+        let zeroed = OwnedDynValue::zeroed(td_data()).cast::<Data>().unwrap();
+        assert!(zeroed.points.is_empty() && zeroed.va.is_empty() && zeroed.vu.is_empty());
+
+        let err = DynValueBuilder::new(td_point()).field("nice", 1_u8).unwrap_err();
+        assert_eq!(
+            err,
+            DynValueError::TypeMismatch { expected: TD::Boolean, actual: u8::type_descriptor() }
+        );
+        let err = DynValueBuilder::new(td_point()).field("foo", true).unwrap_err();
+        assert_eq!(err, DynValueError::UnknownField("foo".into()));
+        let err = DynValueBuilder::new(td_color()).field("foo", true).unwrap_err();
+        assert_eq!(err, DynValueError::InvalidType(td_color()));
+        let err = OwnedDynValue::from_enum_member(td_color(), "Yellow").unwrap_err();
+        assert_eq!(err, DynValueError::UnknownEnumMember("Yellow".into()));
+        let err = OwnedDynValue::from_elements(td_point_coords(), vec![]).unwrap_err();
+        assert_eq!(err, DynValueError::LengthMismatch { expected: 2, actual: 0 });
+        let err = DynValueBuilder::new(td_point_coords()).element(2, 0_f32).unwrap_err();
+        assert_eq!(err, DynValueError::IndexOutOfBounds { index: 2, len: 2 });
+        let coords = DynValueBuilder::new(td_point_coords()).element(1, 3_f32).unwrap().finish();
+        assert_eq!(coords.cast::<[f32; 2]>().unwrap(), [0., 3.]);
+    }
+
+    #[test]
+    fn test_dyn_value_from_str() {
+        let check = |tp: TD, s: &str, expected: &[u8]| {
+            let value = OwnedDynValue::from_str_as(tp, s).unwrap();
+            assert_eq!(unsafe { value.get_buf() }, expected);
+        };
+        check(TD::FixedAscii(4, StringPadding::NullPad), "ab", b"ab\0\0");
+        check(TD::FixedAscii(4, StringPadding::SpacePad), "ab", b"ab  ");
+        check(TD::FixedUnicode(4, StringPadding::NullTerm), "ö", b"\xc3\xb6\0\0");
+
+        let value = OwnedDynValue::from_str_as(TD::VarLenUnicode, "öö").unwrap();
+        assert_eq!(value.cast::<VarLenUnicode>().unwrap().as_str(), "öö");
+
+        let err = |tp: TD, s: &str| OwnedDynValue::from_str_as(tp, s).unwrap_err();
+        let fixed = TD::FixedAscii(2, StringPadding::NullPad);
+        assert_eq!(err(fixed.clone(), "abc"), StringError::InsufficientCapacity.into());
+        assert_eq!(err(fixed.clone(), "a\0"), StringError::InternalNull.into());
+        assert!(matches!(err(fixed, "ö"), DynValueError::StringError(StringError::AsciiError(_))));
+        assert!(matches!(
+            err(TD::VarLenAscii, "ö"),
+            DynValueError::StringError(StringError::AsciiError(_))
+        ));
+        assert_eq!(err(TD::Boolean, "a"), DynValueError::InvalidType(TD::Boolean));
+    }
+
     #[test]
     fn test_dyn_value_display() {
         let val1 = OwnedDynValue::new(big_struct_1());
@@ -1114,4 +2066,74 @@ mod tests {
         assert_eq!(format!("{:?}", val2), val2_flat);
         assert_eq!(format!("{:#?}", val2.clone()), val2_nice);
     }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_dyn_value_serialize() {
+        use serde_json::json;
+
+        let data = Data {
+            points: VarLenArray::from_slice(
+                [
+                    Point { coords: [-1.0, 2.0], color: Color::Red, nice: true },
+                    Point { coords: [0.5, 0.], color: Color::Blue, nice: false },
+                ]
+                .as_ref(),
+            ),
+            fa: FixedAscii::from_ascii(b"12345").unwrap(),
+            fu: FixedUnicode::from_str("∀").unwrap(),
+            va: VarLenAscii::from_ascii(b"wat").unwrap(),
+            vu: VarLenUnicode::from_str("⨁∀").unwrap(),
+        };
+        let value = serde_json::to_value(OwnedDynValue::new(data)).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "points": [
+                    {"coords": [-1.0, 2.0], "color": "Red", "nice": true},
+                    {"coords": [0.5, 0.0], "color": "Blue", "nice": false},
+                ],
+                "fa": "12345",
+                "fu": "∀",
+                "va": "wat",
+                "vu": "⨁∀",
+            })
+        );
+
+        let unknown = DynEnum::new(
+            match td_color() {
+                TD::Enum(tp) => Box::leak(Box::new(tp)),
+                _ => unreachable!(),
+            },
+            DynInteger::Int16(42),
+        );
+        assert_eq!(serde_json::to_string(&unknown).unwrap(), "42");
+        assert_eq!(
+            serde_json::to_string(&OwnedDynValue::new(u64::MAX)).unwrap(),
+            "18446744073709551615"
+        );
+        assert_eq!(
+            serde_json::to_string(&OwnedDynValue::new([[1u8, 2], [3, 4]])).unwrap(),
+            "[[1,2],[3,4]]"
+        );
+
+        let tp = TD::FixedAscii(2, StringPadding::NullPad);
+        let err = serde_json::to_string(&DynValue::new(&tp, &[0xff, 0])).unwrap_err();
+        assert!(err.to_string().contains("not valid UTF-8"));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_type_descriptor_serde_roundtrip() {
+        for desc in &[
+            td_data(),
+            TD::FixedUnicode(8, StringPadding::SpacePad),
+            TD::Opaque { size: 4, tag: "tag".into() },
+            TD::Reference(Reference::Object),
+            TD::Bitfield(IntSize::U2),
+        ] {
+            let json = serde_json::to_string(desc).unwrap();
+            assert_eq!(&serde_json::from_str::<TD>(&json).unwrap(), desc);
+        }
+    }
 }
@@ -3,7 +3,14 @@ use std::mem;
 use std::os::raw::c_void;
 use std::ptr;
 
+#[cfg(feature = "1.12.0")]
+use hdf5_sys::h5r::H5R_ref_t;
+use hdf5_sys::h5r::{hdset_reg_ref_t, hobj_ref_t};
+
 use crate::array::VarLenArray;
+use crate::datetime::{TimeDelta, Timestamp};
+use crate::opaque::{Bitfield, Opaque};
+use crate::references::{ObjectReference, RegionReference};
 use crate::string::{FixedAscii, FixedUnicode, VarLenAscii, VarLenUnicode};
 
 #[allow(non_camel_case_types)]
@@ -15,6 +22,7 @@ pub(crate) struct hvl_t {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum IntSize {
     U1 = 1,
     U2 = 2,
@@ -36,17 +44,37 @@ impl IntSize {
             None
         }
     }
+
+    /// Returns the smallest integer size that can hold the given number of bits.
+    pub const fn from_precision(bits: usize) -> Option<Self> {
+        if bits <= 8 {
+            Some(Self::U1)
+        } else if bits <= 16 {
+            Some(Self::U2)
+        } else if bits <= 32 {
+            Some(Self::U4)
+        } else if bits <= 64 {
+            Some(Self::U8)
+        } else {
+            None
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FloatSize {
+    /// Half precision; the `half` feature implements `H5Type` for `half::f16`.
+    U2 = 2,
     U4 = 4,
     U8 = 8,
 }
 
 impl FloatSize {
     pub const fn from_int(size: usize) -> Option<Self> {
-        if size == 4 {
+        if size == 2 {
+            Some(Self::U2)
+        } else if size == 4 {
             Some(Self::U4)
         } else if size == 8 {
             Some(Self::U8)
@@ -56,13 +84,75 @@ impl FloatSize {
     }
 }
 
+/// Padding of fixed-length strings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StringPadding {
+    /// Null-terminated (as in C); anything past the first null byte is ignored.
+    NullTerm,
+    /// Padded with null bytes; this is what `FixedAscii` and `FixedUnicode` use.
+    NullPad,
+    /// Padded with spaces (as in Fortran); trailing spaces are ignored.
+    SpacePad,
+}
+
+impl Default for StringPadding {
+    fn default() -> Self {
+        Self::NullPad
+    }
+}
+
+impl Display for StringPadding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::NullTerm => "null-terminated",
+            Self::NullPad => "null-padded",
+            Self::SpacePad => "space-padded",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Reference {
+    /// Reference to a named object (`hobj_ref_t`).
+    Object,
+    /// Reference to a region of a dataset (`hdset_reg_ref_t`).
+    Region,
+    /// Reference to an object, a dataset region or an attribute (`H5R_ref_t`).
+    #[cfg(feature = "1.12.0")]
+    Std,
+}
+
+impl Reference {
+    pub fn size(self) -> usize {
+        match self {
+            Self::Object => mem::size_of::<hobj_ref_t>(),
+            Self::Region => mem::size_of::<hdset_reg_ref_t>(),
+            #[cfg(feature = "1.12.0")]
+            Self::Std => mem::size_of::<H5R_ref_t>(),
+        }
+    }
+
+    fn c_alignment(self) -> usize {
+        match self {
+            Self::Object => mem::align_of::<hobj_ref_t>(),
+            Self::Region => mem::align_of::<hdset_reg_ref_t>(),
+            #[cfg(feature = "1.12.0")]
+            Self::Std => mem::align_of::<H5R_ref_t>(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EnumMember {
     pub name: String,
     pub value: u64,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EnumType {
     pub size: IntSize,
     pub signed: bool,
@@ -81,6 +171,7 @@ impl EnumType {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CompoundField {
     pub name: String,
     pub ty: TypeDescriptor,
@@ -99,6 +190,7 @@ impl CompoundField {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CompoundType {
     pub fields: Vec<CompoundField>,
     pub size: usize,
@@ -141,6 +233,7 @@ impl CompoundType {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TypeDescriptor {
     Integer(IntSize),
     Unsigned(IntSize),
@@ -149,16 +242,65 @@ pub enum TypeDescriptor {
     Enum(EnumType),
     Compound(CompoundType),
     FixedArray(Box<Self>, usize),
-    FixedAscii(usize),
-    FixedUnicode(usize),
+    FixedAscii(usize, StringPadding),
+    FixedUnicode(usize, StringPadding),
     VarLenArray(Box<Self>),
     VarLenAscii,
     VarLenUnicode,
+    Reference(Reference),
+    Opaque { size: usize, tag: String },
+    Bitfield(IntSize),
+}
+
+fn fmt_name(f: &mut fmt::Formatter, name: &str) -> fmt::Result {
+    if crate::parse::is_plain_name(name) {
+        f.write_str(name)
+    } else {
+        write!(f, "{:?}", name)
+    }
 }
 
 impl Display for TypeDescriptor {
+    /// The alternate form (`{:#}`) also lists members of enums and fields of compounds;
+    /// both forms can be parsed back via `FromStr`.
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
+            TypeDescriptor::Enum(ref tp) if f.alternate() => {
+                write!(f, "enum ({}) {{", tp.base_type())?;
+                for (i, member) in tp.members.iter().enumerate() {
+                    f.write_str(if i == 0 { "" } else { ", " })?;
+                    fmt_name(f, &member.name)?;
+                    if tp.signed {
+                        write!(f, " = {}", member.value as i64)?;
+                    } else {
+                        write!(f, " = {}", member.value)?;
+                    }
+                }
+                f.write_str("}")
+            }
+            TypeDescriptor::Compound(ref tp) if f.alternate() => {
+                let explicit = *tp != tp.to_c_repr();
+                if explicit {
+                    write!(f, "compound (size {}) {{", tp.size)?;
+                } else {
+                    f.write_str("compound {")?;
+                }
+                for (i, field) in tp.fields.iter().enumerate() {
+                    f.write_str(if i == 0 { "" } else { ", " })?;
+                    fmt_name(f, &field.name)?;
+                    if explicit {
+                        write!(f, " @ {}", field.offset)?;
+                    }
+                    write!(f, ": {:#}", field.ty)?;
+                }
+                f.write_str("}")
+            }
+            TypeDescriptor::FixedArray(ref tp, n) if f.alternate() => {
+                write!(f, "[{:#}; {}]", tp, n)
+            }
+            TypeDescriptor::VarLenArray(ref tp) if f.alternate() => {
+                write!(f, "[{:#}] (var len)", tp)
+            }
             TypeDescriptor::Integer(IntSize::U1) => write!(f, "int8"),
             TypeDescriptor::Integer(IntSize::U2) => write!(f, "int16"),
             TypeDescriptor::Integer(IntSize::U4) => write!(f, "int32"),
@@ -167,17 +309,35 @@ impl Display for TypeDescriptor {
             TypeDescriptor::Unsigned(IntSize::U2) => write!(f, "uint16"),
             TypeDescriptor::Unsigned(IntSize::U4) => write!(f, "uint32"),
             TypeDescriptor::Unsigned(IntSize::U8) => write!(f, "uint64"),
+            TypeDescriptor::Float(FloatSize::U2) => write!(f, "float16"),
             TypeDescriptor::Float(FloatSize::U4) => write!(f, "float32"),
             TypeDescriptor::Float(FloatSize::U8) => write!(f, "float64"),
             TypeDescriptor::Boolean => write!(f, "bool"),
             TypeDescriptor::Enum(ref tp) => write!(f, "enum ({})", tp.base_type()),
             TypeDescriptor::Compound(ref tp) => write!(f, "compound ({} fields)", tp.fields.len()),
             TypeDescriptor::FixedArray(ref tp, n) => write!(f, "[{}; {}]", tp, n),
-            TypeDescriptor::FixedAscii(n) => write!(f, "string (len {})", n),
-            TypeDescriptor::FixedUnicode(n) => write!(f, "unicode (len {})", n),
+            TypeDescriptor::FixedAscii(n, StringPadding::NullPad) => {
+                write!(f, "string (len {})", n)
+            }
+            TypeDescriptor::FixedUnicode(n, StringPadding::NullPad) => {
+                write!(f, "unicode (len {})", n)
+            }
+            TypeDescriptor::FixedAscii(n, pad) => write!(f, "string (len {}, {})", n, pad),
+            TypeDescriptor::FixedUnicode(n, pad) => write!(f, "unicode (len {}, {})", n, pad),
             TypeDescriptor::VarLenArray(ref tp) => write!(f, "[{}] (var len)", tp),
             TypeDescriptor::VarLenAscii => write!(f, "string (var len)"),
             TypeDescriptor::VarLenUnicode => write!(f, "unicode (var len)"),
+            TypeDescriptor::Reference(Reference::Object) => write!(f, "reference (object)"),
+            TypeDescriptor::Reference(Reference::Region) => write!(f, "reference (region)"),
+            #[cfg(feature = "1.12.0")]
+            TypeDescriptor::Reference(Reference::Std) => write!(f, "reference"),
+            TypeDescriptor::Opaque { size, ref tag } if tag.is_empty() => {
+                write!(f, "opaque (len {})", size)
+            }
+            TypeDescriptor::Opaque { size, ref tag } => {
+                write!(f, "opaque (len {}, tag {:?})", size, tag)
+            }
+            TypeDescriptor::Bitfield(size) => write!(f, "bitfield{}", *size as usize * 8),
         }
     }
 }
@@ -191,9 +351,12 @@ impl TypeDescriptor {
             Self::Enum(ref enum_type) => enum_type.size as _,
             Self::Compound(ref compound) => compound.size,
             Self::FixedArray(ref ty, len) => ty.size() * len,
-            Self::FixedAscii(len) | Self::FixedUnicode(len) => len,
+            Self::FixedAscii(len, _) | Self::FixedUnicode(len, _) => len,
             Self::VarLenArray(_) => mem::size_of::<hvl_t>(),
             Self::VarLenAscii | Self::VarLenUnicode => mem::size_of::<*const u8>(),
+            Self::Reference(reference) => reference.size(),
+            Self::Opaque { size, .. } => size,
+            Self::Bitfield(size) => size as _,
         }
     }
 
@@ -203,8 +366,9 @@ impl TypeDescriptor {
                 compound.fields.iter().map(|f| f.ty.c_alignment()).max().unwrap_or(1)
             }
             Self::FixedArray(ref ty, _) => ty.c_alignment(),
-            Self::FixedAscii(_) | Self::FixedUnicode(_) => 1,
+            Self::FixedAscii(..) | Self::FixedUnicode(..) | Self::Opaque { .. } => 1,
             Self::VarLenArray(_) => mem::size_of::<usize>(),
+            Self::Reference(reference) => reference.c_alignment(),
             _ => self.size(),
         }
     }
@@ -230,6 +394,25 @@ impl TypeDescriptor {
 
 pub unsafe trait H5Type: 'static {
     fn type_descriptor() -> TypeDescriptor;
+
+    /// Names of the compound fields that have default values.
+    ///
+    /// When reading compound data, these fields may be missing from the source datatype, in
+    /// which case they are filled in via [`write_default_field()`](Self::write_default_field)
+    /// (see `#[hdf5(default)]` in `#[derive(H5Type)]`).
+    #[inline]
+    fn default_fields() -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Writes the default value of the field `name` (one of `default_fields()`) into `dst`.
+    ///
+    /// # Safety
+    ///
+    /// `dst` must point to memory valid for writes of `Self`; the previous contents of the
+    /// field are overwritten without being dropped.
+    #[inline]
+    unsafe fn write_default_field(_dst: *mut Self, _name: &str) {}
 }
 
 macro_rules! impl_h5type {
@@ -254,6 +437,34 @@ impl_h5type!(u64, Unsigned, IntSize::U8);
 impl_h5type!(f32, Float, FloatSize::U4);
 impl_h5type!(f64, Float, FloatSize::U8);
 
+#[cfg(feature = "half")]
+impl_h5type!(half::f16, Float, FloatSize::U2);
+
+#[cfg(feature = "complex")]
+macro_rules! impl_complex {
+    ($ty:ty) => {
+        unsafe impl H5Type for num_complex::Complex<$ty> {
+            #[inline]
+            fn type_descriptor() -> TypeDescriptor {
+                // same layout as numpy's complex types written by h5py
+                let size = mem::size_of::<$ty>();
+                TypeDescriptor::Compound(CompoundType {
+                    fields: vec![
+                        CompoundField::typed::<$ty>("r", 0, 0),
+                        CompoundField::typed::<$ty>("i", size, 1),
+                    ],
+                    size: 2 * size,
+                })
+            }
+        }
+    };
+}
+
+#[cfg(feature = "complex")]
+impl_complex!(f32);
+#[cfg(feature = "complex")]
+impl_complex!(f64);
+
 #[cfg(target_pointer_width = "32")]
 impl_h5type!(isize, Integer, IntSize::U4);
 #[cfg(target_pointer_width = "32")]
@@ -330,7 +541,7 @@ unsafe impl<T: H5Type, const N: usize> H5Type for [T; N] {
     }
 }
 
-unsafe impl<T: Copy + H5Type> H5Type for VarLenArray<T> {
+unsafe impl<T: H5Type> H5Type for VarLenArray<T> {
     #[inline]
     fn type_descriptor() -> TypeDescriptor {
         TypeDescriptor::VarLenArray(Box::new(<T as H5Type>::type_descriptor()))
@@ -340,14 +551,14 @@ unsafe impl<T: Copy + H5Type> H5Type for VarLenArray<T> {
 unsafe impl<const N: usize> H5Type for FixedAscii<N> {
     #[inline]
     fn type_descriptor() -> TypeDescriptor {
-        TypeDescriptor::FixedAscii(N)
+        TypeDescriptor::FixedAscii(N, StringPadding::NullPad)
     }
 }
 
 unsafe impl<const N: usize> H5Type for FixedUnicode<N> {
     #[inline]
     fn type_descriptor() -> TypeDescriptor {
-        TypeDescriptor::FixedUnicode(N)
+        TypeDescriptor::FixedUnicode(N, StringPadding::NullPad)
     }
 }
 
@@ -365,14 +576,69 @@ unsafe impl H5Type for VarLenUnicode {
     }
 }
 
+unsafe impl H5Type for ObjectReference {
+    #[inline]
+    fn type_descriptor() -> TypeDescriptor {
+        TypeDescriptor::Reference(Reference::Object)
+    }
+}
+
+unsafe impl H5Type for RegionReference {
+    #[inline]
+    fn type_descriptor() -> TypeDescriptor {
+        TypeDescriptor::Reference(Reference::Region)
+    }
+}
+
+unsafe impl<const N: usize> H5Type for Opaque<N> {
+    #[inline]
+    fn type_descriptor() -> TypeDescriptor {
+        TypeDescriptor::Opaque { size: N, tag: String::new() }
+    }
+}
+
+impl_h5type!(Bitfield<u8>, Bitfield, IntSize::U1);
+impl_h5type!(Bitfield<u16>, Bitfield, IntSize::U2);
+impl_h5type!(Bitfield<u32>, Bitfield, IntSize::U4);
+impl_h5type!(Bitfield<u64>, Bitfield, IntSize::U8);
+
+unsafe impl H5Type for Timestamp {
+    #[inline]
+    fn type_descriptor() -> TypeDescriptor {
+        TypeDescriptor::Integer(IntSize::U8)
+    }
+}
+
+unsafe impl H5Type for TimeDelta {
+    #[inline]
+    fn type_descriptor() -> TypeDescriptor {
+        TypeDescriptor::Integer(IntSize::U8)
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::TypeDescriptor as TD;
-    use super::{hvl_t, CompoundField, CompoundType, FloatSize, H5Type, IntSize};
+    use super::{
+        hvl_t, CompoundField, CompoundType, FloatSize, H5Type, IntSize, Reference, StringPadding,
+    };
     use crate::array::VarLenArray;
+    use crate::opaque::{Bitfield, Opaque};
+    use crate::references::{ObjectReference, RegionReference};
     use crate::string::{FixedAscii, FixedUnicode, VarLenAscii, VarLenUnicode};
     use std::mem;
 
+    #[test]
+    pub fn test_int_size() {
+        assert_eq!(IntSize::from_int(3), None);
+        assert_eq!(IntSize::from_int(4), Some(IntSize::U4));
+        assert_eq!(IntSize::from_precision(1), Some(IntSize::U1));
+        assert_eq!(IntSize::from_precision(12), Some(IntSize::U2));
+        assert_eq!(IntSize::from_precision(24), Some(IntSize::U4));
+        assert_eq!(IntSize::from_precision(64), Some(IntSize::U8));
+        assert_eq!(IntSize::from_precision(65), None);
+    }
+
     #[test]
     pub fn test_scalar_types() {
         assert_eq!(bool::type_descriptor(), TD::Boolean);
@@ -386,6 +652,8 @@ pub mod tests {
         assert_eq!(u64::type_descriptor(), TD::Unsigned(IntSize::U8));
         assert_eq!(f32::type_descriptor(), TD::Float(FloatSize::U4));
         assert_eq!(f64::type_descriptor(), TD::Float(FloatSize::U8));
+        #[cfg(feature = "half")]
+        assert_eq!(half::f16::type_descriptor(), TD::Float(FloatSize::U2));
 
         assert_eq!(bool::type_descriptor().size(), 1);
         assert_eq!(i16::type_descriptor().size(), 2);
@@ -430,12 +698,83 @@ pub mod tests {
     pub fn test_string_types() {
         type FA = FixedAscii<16>;
         type FU = FixedUnicode<32>;
-        assert_eq!(FA::type_descriptor(), TD::FixedAscii(16));
-        assert_eq!(FU::type_descriptor(), TD::FixedUnicode(32));
+        assert_eq!(FA::type_descriptor(), TD::FixedAscii(16, StringPadding::NullPad));
+        assert_eq!(FU::type_descriptor(), TD::FixedUnicode(32, StringPadding::NullPad));
+        assert_eq!(format!("{}", FA::type_descriptor()), "string (len 16)");
+        let td = TD::FixedAscii(8, StringPadding::SpacePad);
+        assert_eq!(format!("{}", td), "string (len 8, space-padded)");
+        let td = TD::FixedUnicode(4, StringPadding::NullTerm);
+        assert_eq!(format!("{}", td), "unicode (len 4, null-terminated)");
         assert_eq!(VarLenAscii::type_descriptor(), TD::VarLenAscii);
         assert_eq!(VarLenUnicode::type_descriptor(), TD::VarLenUnicode);
     }
 
+    #[test]
+    pub fn test_reference_types() {
+        assert_eq!(ObjectReference::type_descriptor(), TD::Reference(Reference::Object));
+        assert_eq!(ObjectReference::type_descriptor().size(), 8);
+        assert_eq!(format!("{}", ObjectReference::type_descriptor()), "reference (object)");
+        assert_eq!(RegionReference::type_descriptor(), TD::Reference(Reference::Region));
+        assert_eq!(RegionReference::type_descriptor().size(), 12);
+        assert_eq!(format!("{}", RegionReference::type_descriptor()), "reference (region)");
+
+        #[repr(C)]
+        struct T {
+            a: u8,
+            b: RegionReference,
+        }
+        let td = TD::Compound(CompoundType {
+            fields: vec![
+                CompoundField::typed::<u8>("a", 0, 0),
+                CompoundField::typed::<RegionReference>("b", 1, 1),
+            ],
+            size: 13,
+        });
+        assert_eq!(td.to_c_repr().size(), mem::size_of::<T>());
+    }
+
+    #[test]
+    #[cfg(feature = "complex")]
+    pub fn test_complex_types() {
+        use num_complex::{Complex32, Complex64};
+
+        assert_eq!(
+            Complex32::type_descriptor(),
+            TD::Compound(CompoundType {
+                fields: vec![
+                    CompoundField::typed::<f32>("r", 0, 0),
+                    CompoundField::typed::<f32>("i", 4, 1),
+                ],
+                size: 8,
+            })
+        );
+        assert_eq!(Complex64::type_descriptor().size(), mem::size_of::<Complex64>());
+        assert_eq!(Complex64::type_descriptor().to_c_repr(), Complex64::type_descriptor());
+    }
+
+    #[test]
+    pub fn test_opaque_bitfield_types() {
+        assert_eq!(Opaque::<16>::type_descriptor(), TD::Opaque { size: 16, tag: "".into() });
+        assert_eq!(Opaque::<16>::type_descriptor().size(), 16);
+        assert_eq!(format!("{}", Opaque::<3>::type_descriptor()), "opaque (len 3)");
+        let td = TD::Opaque { size: 4, tag: "vendor blob".into() };
+        assert_eq!(format!("{}", td), "opaque (len 4, tag \"vendor blob\")");
+        assert_eq!(Bitfield::<u8>::type_descriptor(), TD::Bitfield(IntSize::U1));
+        assert_eq!(Bitfield::<u32>::type_descriptor(), TD::Bitfield(IntSize::U4));
+        assert_eq!(Bitfield::<u64>::type_descriptor().size(), 8);
+        assert_eq!(format!("{}", Bitfield::<u16>::type_descriptor()), "bitfield16");
+
+        let td = TD::Compound(CompoundType {
+            fields: vec![
+                CompoundField::typed::<u8>("a", 0, 0),
+                CompoundField::typed::<Opaque<3>>("b", 1, 1),
+                CompoundField::typed::<Bitfield<u32>>("c", 4, 2),
+            ],
+            size: 8,
+        });
+        assert_eq!(td.to_c_repr(), td);
+    }
+
     #[test]
     pub fn test_tuples() {
         type T1 = (u16,);
@@ -12,21 +12,39 @@
 //!                     in different libraries (e.g. dynamic libraries on windows),
 //!                     or if `hdf5-c` is compiled with the MEMCHECKER option.
 //!                     This option is forced on in the case of using a `windows` DLL.
+//! * `half`: Implement `H5Type` for `half::f16` (stored as a 16-bit IEEE float).
+//! * `complex`: Implement `H5Type` for `num_complex::Complex<f32>` and `Complex<f64>`
+//!              (stored as compounds with fields `r` and `i`, like `h5py` does).
+//! * `serde`: Implement `Serialize` for `DynValue` and `OwnedDynValue`, and `Serialize`
+//!            and `Deserialize` for `TypeDescriptor` and its components.
+//! * `chrono`: Conversions between `Timestamp`/`TimeDelta` and `chrono` date/time and
+//!             duration types.
+//! * `time`: Conversions between `Timestamp`/`TimeDelta` and `time::OffsetDateTime` and
+//!           `time::Duration`.
 
 #[cfg(test)]
 #[macro_use]
 extern crate quickcheck;
 
 mod array;
+mod datetime;
 pub mod dyn_value;
 mod h5type;
+mod opaque;
+mod parse;
+mod references;
 mod string;
 
 pub use self::array::VarLenArray;
-pub use self::dyn_value::{DynValue, OwnedDynValue};
+pub use self::datetime::{TimeDelta, TimeRangeError, Timestamp, TIMESTAMP_UNITS, TIME_DELTA_UNITS};
+pub use self::dyn_value::{DynValue, DynValueBuilder, DynValueError, OwnedDynValue};
 pub use self::h5type::{
-    CompoundField, CompoundType, EnumMember, EnumType, FloatSize, H5Type, IntSize, TypeDescriptor,
+    CompoundField, CompoundType, EnumMember, EnumType, FloatSize, H5Type, IntSize, Reference,
+    StringPadding, TypeDescriptor,
 };
+pub use self::opaque::{Bitfield, Opaque};
+pub use self::parse::ParseTypeError;
+pub use self::references::{ObjectReference, RegionReference};
 pub use self::string::{FixedAscii, FixedUnicode, StringError, VarLenAscii, VarLenUnicode};
 
 pub(crate) unsafe fn malloc(n: usize) -> *mut core::ffi::c_void {
@@ -0,0 +1,111 @@
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+/// A fixed-size untagged binary blob, stored as `H5T_OPAQUE`.
+///
+/// HDF5 only converts between opaque types with matching tags; to map a Rust type onto a
+/// tagged opaque type, implement `H5Type` for it manually and return
+/// `TypeDescriptor::Opaque { size, tag }`.
+#[repr(transparent)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Opaque<const N: usize>(pub [u8; N]);
+
+impl<const N: usize> Opaque<N> {
+    #[inline]
+    pub const fn new(bytes: [u8; N]) -> Self {
+        Self(bytes)
+    }
+
+    #[inline]
+    pub const fn into_inner(self) -> [u8; N] {
+        self.0
+    }
+}
+
+impl<const N: usize> Default for Opaque<N> {
+    #[inline]
+    fn default() -> Self {
+        Self([0; N])
+    }
+}
+
+impl<const N: usize> Deref for Opaque<N> {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<const N: usize> DerefMut for Opaque<N> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for Opaque<N> {
+    #[inline]
+    fn from(bytes: [u8; N]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl<const N: usize> fmt::Debug for Opaque<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_bytes("Opaque", &self.0, f)
+    }
+}
+
+pub(crate) fn fmt_bytes(name: &str, bytes: &[u8], f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}(0x", name)?;
+    for b in bytes {
+        write!(f, "{:02x}", b)?;
+    }
+    f.write_str(")")
+}
+
+/// An unsigned integer stored as `H5T_BITFIELD` (a set of bit flags).
+#[repr(transparent)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Bitfield<T>(pub T);
+
+impl<T: Copy> Bitfield<T> {
+    #[inline]
+    pub const fn new(bits: T) -> Self {
+        Self(bits)
+    }
+
+    #[inline]
+    pub const fn bits(self) -> T {
+        self.0
+    }
+}
+
+impl<T: fmt::Binary> fmt::Debug for Bitfield<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Bitfield({:#0w$b})", self.0, w = 2 + 8 * std::mem::size_of::<T>())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::{Bitfield, Opaque};
+
+    #[test]
+    pub fn test_opaque() {
+        let blob = Opaque::new([0xde, 0xad, 0x00, 0x01]);
+        assert_eq!(&*blob, &[0xde, 0xad, 0x00, 0x01]);
+        assert_eq!(blob, Opaque::from([0xde, 0xad, 0x00, 0x01]));
+        assert_eq!(Opaque::<2>::default().into_inner(), [0, 0]);
+        assert_eq!(format!("{:?}", blob), "Opaque(0xdead0001)");
+    }
+
+    #[test]
+    pub fn test_bitfield() {
+        assert_eq!(Bitfield::new(0b101_u8).bits(), 5);
+        assert_eq!(format!("{:?}", Bitfield(0b101_u8)), "Bitfield(0b00000101)");
+        assert_eq!(format!("{:?}", Bitfield(1_u16)), "Bitfield(0b0000000000000001)");
+    }
+}
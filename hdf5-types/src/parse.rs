@@ -0,0 +1,693 @@
+//! Parsing type descriptors back from their textual representation.
+//!
+//! The grammar accepted by `TypeDescriptor::from_str()` is the one produced by its `Display`
+//! implementation. Since the default form omits members of enums and fields of compounds
+//! (parsing it fails with an error saying so), those must be spelled out as in the alternate
+//! (`{:#}`) form:
+//!
+//! ```text
+//! enum (int16) {Red = -1, Green = 0, Blue = 1}
+//! compound {coords: [float32; 2], name: string (var len)}
+//! compound (size 16) {a @ 0: int32, b @ 8: float64}
+//! ```
+//!
+//! Compound fields without explicit offsets are laid out as in a `#[repr(C)]` struct; names
+//! which are not made of alphanumeric characters and underscores must be quoted.
+
+use std::error::Error as StdError;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use crate::h5type::{
+    CompoundField, CompoundType, EnumMember, EnumType, FloatSize, IntSize, Reference,
+    StringPadding, TypeDescriptor,
+};
+
+/// An error which can be returned when parsing a `TypeDescriptor`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseTypeError {
+    position: usize,
+    message: String,
+}
+
+impl ParseTypeError {
+    fn new(position: usize, message: impl Into<String>) -> Self {
+        Self { position, message: message.into() }
+    }
+
+    /// Returns the byte offset in the input at which the error was detected.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the description of the error, without the position.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl StdError for ParseTypeError {}
+
+impl Display for ParseTypeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid type descriptor at position {}: {}", self.position, self.message)
+    }
+}
+
+type Result<T> = std::result::Result<T, ParseTypeError>;
+
+/// Error message for enum members or compound fields omitted by the plain `Display` form.
+fn missing_members(what: &str) -> String {
+    format!("{} are missing; only the alternate (`{{:#}}`) form of a type includes them", what)
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Returns true if a compound field or an enum member name can be written without quotes.
+pub(crate) fn is_plain_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(is_name_char)
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input, pos: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn skip_ws(&mut self) {
+        self.pos = self.input.len() - self.rest().trim_start().len();
+    }
+
+    fn fail<T>(&self, position: usize, message: impl Into<String>) -> Result<T> {
+        Err(ParseTypeError::new(position, message))
+    }
+
+    fn describe_next(&mut self) -> String {
+        self.skip_ws();
+        let rest = self.rest();
+        match rest.chars().next() {
+            None => "end of input".into(),
+            Some(c) if is_name_char(c) => {
+                let len = rest.find(|c| !is_name_char(c)).unwrap_or(rest.len());
+                format!("`{}`", &rest[..len])
+            }
+            Some(c) => format!("`{}`", c),
+        }
+    }
+
+    fn unexpected<T>(&mut self, expected: &str) -> Result<T> {
+        let found = self.describe_next();
+        self.fail(self.pos, format!("expected {}, found {}", expected, found))
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_ws();
+        if self.rest().starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<()> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            self.unexpected(&format!("`{}`", token))
+        }
+    }
+
+    fn word(&mut self) -> Option<&'a str> {
+        self.skip_ws();
+        let rest = self.rest();
+        let len = rest.find(|c| !is_name_char(c)).unwrap_or(rest.len());
+        if len == 0 {
+            return None;
+        }
+        self.pos += len;
+        Some(&rest[..len])
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let pos = self.pos;
+        if self.word() == Some(keyword) {
+            true
+        } else {
+            self.pos = pos;
+            false
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<()> {
+        if self.keyword(keyword) {
+            Ok(())
+        } else {
+            self.unexpected(&format!("`{}`", keyword))
+        }
+    }
+
+    fn number(&mut self) -> Result<u64> {
+        self.skip_ws();
+        let start = self.pos;
+        let rest = self.rest();
+        let len = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        if len == 0 {
+            return self.unexpected("a number");
+        }
+        self.pos += len;
+        rest[..len].parse().or_else(|_| self.fail(start, "number is too large"))
+    }
+
+    fn size(&mut self) -> Result<usize> {
+        let start = self.pos;
+        let n = self.number()?;
+        if n as usize as u64 != n {
+            return self.fail(start, "number is too large");
+        }
+        Ok(n as _)
+    }
+
+    fn string_literal(&mut self) -> Result<String> {
+        if !self.eat("\"") {
+            return self.unexpected("a quoted string");
+        }
+        let mut out = String::new();
+        loop {
+            let c = match self.rest().chars().next() {
+                Some(c) => c,
+                None => return self.fail(self.pos, "unterminated string"),
+            };
+            let start = self.pos;
+            self.pos += c.len_utf8();
+            let c = match c {
+                '"' => return Ok(out),
+                '\\' => {
+                    let escaped = self.rest().chars().next();
+                    self.pos += escaped.map_or(0, char::len_utf8);
+                    match escaped {
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('0') => '\0',
+                        Some(c) if c == '\\' || c == '"' || c == '\'' => c,
+                        Some('u') => {
+                            let rest = self.rest();
+                            let hex =
+                                rest.strip_prefix('{').and_then(|s| s.find('}').map(|i| &s[..i]));
+                            match hex
+                                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                                .and_then(std::char::from_u32)
+                            {
+                                Some(c) => {
+                                    self.pos += hex.map_or(0, str::len) + 2;
+                                    c
+                                }
+                                None => return self.fail(start, "invalid unicode escape"),
+                            }
+                        }
+                        _ => return self.fail(start, "invalid escape sequence"),
+                    }
+                }
+                c => c,
+            };
+            out.push(c);
+        }
+    }
+
+    fn name(&mut self) -> Result<String> {
+        self.skip_ws();
+        if self.rest().starts_with('"') {
+            self.string_literal()
+        } else {
+            match self.word() {
+                Some(name) => Ok(name.to_owned()),
+                None => self.unexpected("a name"),
+            }
+        }
+    }
+
+    /// Parses a comma-separated list of items up to and including the closing brace.
+    fn list<T>(&mut self, mut item: impl FnMut(&mut Self) -> Result<T>) -> Result<Vec<T>> {
+        let mut items = Vec::new();
+        while !self.eat("}") {
+            items.push(item(self)?);
+            if !self.eat(",") {
+                self.expect("}")?;
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    fn parse_type(&mut self) -> Result<TypeDescriptor> {
+        use TypeDescriptor as TD;
+
+        if self.eat("[") {
+            let ty = Box::new(self.parse_type()?);
+            if self.eat(";") {
+                let len = self.size()?;
+                self.expect("]")?;
+                return Ok(TD::FixedArray(ty, len));
+            }
+            self.expect("]")?;
+            self.expect("(")?;
+            self.expect_keyword("var")?;
+            self.expect_keyword("len")?;
+            self.expect(")")?;
+            return Ok(TD::VarLenArray(ty));
+        }
+
+        self.skip_ws();
+        let start = self.pos;
+        let word = match self.word() {
+            Some(word) => word,
+            None => return self.unexpected("a type"),
+        };
+        Ok(match word {
+            "int8" => TD::Integer(IntSize::U1),
+            "int16" => TD::Integer(IntSize::U2),
+            "int32" => TD::Integer(IntSize::U4),
+            "int64" => TD::Integer(IntSize::U8),
+            "uint8" => TD::Unsigned(IntSize::U1),
+            "uint16" => TD::Unsigned(IntSize::U2),
+            "uint32" => TD::Unsigned(IntSize::U4),
+            "uint64" => TD::Unsigned(IntSize::U8),
+            "float16" => TD::Float(FloatSize::U2),
+            "float32" => TD::Float(FloatSize::U4),
+            "float64" => TD::Float(FloatSize::U8),
+            "bool" => TD::Boolean,
+            "bitfield8" => TD::Bitfield(IntSize::U1),
+            "bitfield16" => TD::Bitfield(IntSize::U2),
+            "bitfield32" => TD::Bitfield(IntSize::U4),
+            "bitfield64" => TD::Bitfield(IntSize::U8),
+            "string" => self.parse_string(false)?,
+            "unicode" => self.parse_string(true)?,
+            "reference" => self.parse_reference(start)?,
+            "opaque" => self.parse_opaque()?,
+            "enum" => self.parse_enum()?,
+            "compound" => self.parse_compound()?,
+            _ => return self.fail(start, format!("unknown type `{}`", word)),
+        })
+    }
+
+    fn parse_string(&mut self, unicode: bool) -> Result<TypeDescriptor> {
+        self.expect("(")?;
+        if self.keyword("var") {
+            self.expect_keyword("len")?;
+            self.expect(")")?;
+            return Ok(if unicode {
+                TypeDescriptor::VarLenUnicode
+            } else {
+                TypeDescriptor::VarLenAscii
+            });
+        }
+        self.expect_keyword("len")?;
+        let len = self.size()?;
+        let mut padding = StringPadding::NullPad;
+        if self.eat(",") {
+            padding = if self.eat("null-terminated") {
+                StringPadding::NullTerm
+            } else if self.eat("null-padded") {
+                StringPadding::NullPad
+            } else if self.eat("space-padded") {
+                StringPadding::SpacePad
+            } else {
+                return self.unexpected("`null-terminated`, `null-padded` or `space-padded`");
+            };
+        }
+        self.expect(")")?;
+        Ok(if unicode {
+            TypeDescriptor::FixedUnicode(len, padding)
+        } else {
+            TypeDescriptor::FixedAscii(len, padding)
+        })
+    }
+
+    fn parse_reference(&mut self, start: usize) -> Result<TypeDescriptor> {
+        if self.eat("(") {
+            let reference = if self.keyword("object") {
+                Reference::Object
+            } else if self.keyword("region") {
+                Reference::Region
+            } else {
+                return self.unexpected("`object` or `region`");
+            };
+            self.expect(")")?;
+            return Ok(TypeDescriptor::Reference(reference));
+        }
+        #[cfg(feature = "1.12.0")]
+        {
+            let _ = start;
+            Ok(TypeDescriptor::Reference(Reference::Std))
+        }
+        #[cfg(not(feature = "1.12.0"))]
+        {
+            self.fail(start, "untyped references require HDF5 1.12.0 or newer")
+        }
+    }
+
+    fn parse_opaque(&mut self) -> Result<TypeDescriptor> {
+        self.expect("(")?;
+        self.expect_keyword("len")?;
+        let size = self.size()?;
+        let mut tag = String::new();
+        if self.eat(",") {
+            self.expect_keyword("tag")?;
+            tag = self.string_literal()?;
+        }
+        self.expect(")")?;
+        Ok(TypeDescriptor::Opaque { size, tag })
+    }
+
+    fn parse_enum(&mut self) -> Result<TypeDescriptor> {
+        self.expect("(")?;
+        self.skip_ws();
+        let start = self.pos;
+        let (size, signed) = match self.parse_type()? {
+            TypeDescriptor::Integer(size) => (size, true),
+            TypeDescriptor::Unsigned(size) => (size, false),
+            ty => {
+                let msg = format!("enum base type must be an integer type, found `{}`", ty);
+                return self.fail(start, msg);
+            }
+        };
+        self.expect(")")?;
+        if !self.eat("{") {
+            return self.fail(self.pos, missing_members("enum members"));
+        }
+        let members = self.list(|p| {
+            p.skip_ws();
+            let start = p.pos;
+            let name = p.name()?;
+            p.expect("=")?;
+            p.skip_ws();
+            let value_pos = p.pos;
+            let negative = p.eat("-");
+            let value = p.number()?;
+            let bits = size as u32 * 8;
+            let value = if !signed {
+                if negative || (bits < 64 && value >> bits != 0) {
+                    return p.fail(value_pos, format!("value out of range for `uint{}`", bits));
+                }
+                value
+            } else {
+                let limit = 1_u64 << (bits - 1);
+                if value > limit || (value == limit && !negative) {
+                    return p.fail(value_pos, format!("value out of range for `int{}`", bits));
+                }
+                if negative {
+                    value.wrapping_neg()
+                } else {
+                    value
+                }
+            };
+            Ok((start, EnumMember { name, value }))
+        })?;
+        for (i, (pos, member)) in members.iter().enumerate() {
+            if members[..i].iter().any(|(_, m)| m.name == member.name) {
+                return self.fail(*pos, format!("duplicate enum member `{}`", member.name));
+            }
+        }
+        let members = members.into_iter().map(|(_, member)| member).collect();
+        Ok(TypeDescriptor::Enum(EnumType { size, signed, members }))
+    }
+
+    fn parse_compound(&mut self) -> Result<TypeDescriptor> {
+        let mut size = None;
+        if self.eat("(") {
+            self.skip_ws();
+            let pos = self.pos;
+            if self.number().is_ok() && self.keyword("fields") {
+                return self.fail(pos, missing_members("compound fields"));
+            }
+            self.pos = pos;
+            self.expect_keyword("size")?;
+            size = Some(self.size()?);
+            self.expect(")")?;
+        }
+        if !self.eat("{") {
+            return self.unexpected("`{` followed by compound fields");
+        }
+        let start = self.pos;
+        let fields = self.list(|p| {
+            p.skip_ws();
+            let start = p.pos;
+            let name = p.name()?;
+            let offset = if p.eat("@") { Some(p.size()?) } else { None };
+            p.expect(":")?;
+            let ty = p.parse_type()?;
+            Ok((start, name, offset, ty))
+        })?;
+        if fields.is_empty() {
+            return self.fail(start, "compound type must have at least one field");
+        }
+        for (i, (pos, name, ..)) in fields.iter().enumerate() {
+            if fields[..i].iter().any(|f| f.1 == *name) {
+                return self.fail(*pos, format!("duplicate compound field `{}`", name));
+            }
+        }
+        let positions: Vec<_> = fields.iter().map(|f| f.0).collect();
+        let explicit = fields[0].2.is_some();
+        if let Some((pos, ..)) = fields.iter().find(|f| f.2.is_some() != explicit) {
+            let msg = "either all or none of the compound fields must have offsets";
+            return self.fail(*pos, msg);
+        }
+
+        let mut compound = CompoundType {
+            fields: fields
+                .into_iter()
+                .enumerate()
+                .map(|(index, (_, name, offset, ty))| CompoundField {
+                    name,
+                    ty,
+                    offset: offset.unwrap_or(0),
+                    index,
+                })
+                .collect(),
+            size: 0,
+        };
+        if !explicit {
+            compound = compound.to_c_repr();
+        } else {
+            let mut by_offset: Vec<_> = compound.fields.iter().collect();
+            by_offset.sort_by_key(|f| f.offset);
+            for pair in by_offset.windows(2) {
+                if pair[0].offset + pair[0].ty.size() > pair[1].offset {
+                    let msg = format!(
+                        "compound fields `{}` and `{}` overlap",
+                        pair[0].name, pair[1].name
+                    );
+                    return self.fail(positions[pair[1].index], msg);
+                }
+            }
+            compound.size = by_offset.last().map_or(0, |f| f.offset + f.ty.size());
+        }
+        if let Some(size) = size {
+            if size < compound.size {
+                let msg = format!("compound size {} is less than required {}", size, compound.size);
+                return self.fail(start, msg);
+            }
+            compound.size = size;
+        }
+        Ok(TypeDescriptor::Compound(compound))
+    }
+}
+
+impl FromStr for TypeDescriptor {
+    type Err = ParseTypeError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parser = Parser::new(s);
+        let ty = parser.parse_type()?;
+        parser.skip_ws();
+        if !parser.rest().is_empty() {
+            return parser.unexpected("end of input");
+        }
+        Ok(ty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::h5type::TypeDescriptor as TD;
+
+    fn parse(s: &str) -> TD {
+        s.parse().unwrap_or_else(|err| panic!("failed to parse {:?}: {}", s, err))
+    }
+
+    fn parse_err(s: &str) -> ParseTypeError {
+        s.parse::<TD>().unwrap_err()
+    }
+
+    fn td_enum() -> TD {
+        TD::Enum(EnumType {
+            size: IntSize::U2,
+            signed: true,
+            members: vec![
+                EnumMember { name: "Red".into(), value: -10_000i16 as _ },
+                EnumMember { name: "Green".into(), value: 0 },
+                EnumMember { name: "Dark Blue".into(), value: 32_767 },
+            ],
+        })
+    }
+
+    fn td_compound() -> TD {
+        TD::Compound(
+            CompoundType {
+                fields: vec![
+                    CompoundField::new("color", td_enum(), 0, 0),
+                    CompoundField::new(
+                        "coords",
+                        TD::FixedArray(Box::new(parse("float64")), 3),
+                        0,
+                        1,
+                    ),
+                    CompoundField::new("name", TD::VarLenUnicode, 0, 2),
+                    CompoundField::new("0", TD::Boolean, 0, 3),
+                ],
+                size: 0,
+            }
+            .to_c_repr(),
+        )
+    }
+
+    #[test]
+    fn test_parse_roundtrip() {
+        let descs = vec![
+            TD::Integer(IntSize::U1),
+            TD::Integer(IntSize::U8),
+            TD::Unsigned(IntSize::U2),
+            TD::Unsigned(IntSize::U4),
+            TD::Float(FloatSize::U2),
+            TD::Float(FloatSize::U4),
+            TD::Float(FloatSize::U8),
+            TD::Boolean,
+            TD::FixedArray(Box::new(TD::FixedArray(Box::new(TD::Boolean), 2)), 3),
+            TD::VarLenArray(Box::new(TD::Integer(IntSize::U4))),
+            TD::FixedAscii(8, StringPadding::NullPad),
+            TD::FixedAscii(8, StringPadding::NullTerm),
+            TD::FixedUnicode(3, StringPadding::SpacePad),
+            TD::VarLenAscii,
+            TD::VarLenUnicode,
+            TD::Reference(Reference::Object),
+            TD::Reference(Reference::Region),
+            TD::Opaque { size: 7, tag: "".into() },
+            TD::Opaque { size: 2, tag: "a \"tag\"\n∀".into() },
+            TD::Bitfield(IntSize::U2),
+            td_enum(),
+            td_compound(),
+            TD::VarLenArray(Box::new(td_compound())),
+        ];
+        for desc in descs {
+            assert_eq!(parse(&format!("{:#}", desc)), desc);
+            if !matches!(desc, TD::Enum(_) | TD::Compound(_) | TD::VarLenArray(_)) {
+                assert_eq!(parse(&desc.to_string()), desc);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_syntax() {
+        assert_eq!(parse("  [ int8 ;4 ]  "), TD::FixedArray(Box::new(TD::Integer(IntSize::U1)), 4));
+        assert_eq!(parse("[uint8](var len)"), TD::VarLenArray(Box::new(TD::Unsigned(IntSize::U1))));
+        assert_eq!(
+            parse("unicode (len 4,space-padded)"),
+            TD::FixedUnicode(4, StringPadding::SpacePad)
+        );
+        assert_eq!(
+            parse("enum (uint8) {A = 0, B = 255,}"),
+            TD::Enum(EnumType {
+                size: IntSize::U1,
+                signed: false,
+                members: vec![
+                    EnumMember { name: "A".into(), value: 0 },
+                    EnumMember { name: "B".into(), value: 255 },
+                ],
+            })
+        );
+        assert_eq!(
+            parse("compound {a: int8, b: int32}"),
+            TD::Compound(CompoundType {
+                fields: vec![
+                    CompoundField::new("a", TD::Integer(IntSize::U1), 0, 0),
+                    CompoundField::new("b", TD::Integer(IntSize::U4), 4, 1),
+                ],
+                size: 8,
+            })
+        );
+        assert_eq!(
+            parse("compound (size 16) {b @ 8: int32, a @ 0: int8}"),
+            TD::Compound(CompoundType {
+                fields: vec![
+                    CompoundField::new("b", TD::Integer(IntSize::U4), 8, 0),
+                    CompoundField::new("a", TD::Integer(IntSize::U1), 0, 1),
+                ],
+                size: 16,
+            })
+        );
+        assert_eq!(parse("compound {\"a b\": int8}").to_string(), "compound (1 fields)");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let check = |s: &str, position: usize, message: &str| {
+            let err = parse_err(s);
+            assert_eq!((err.position(), err.message()), (position, message), "{:?}", s);
+        };
+        check("", 0, "expected a type, found end of input");
+        check("int", 0, "unknown type `int`");
+        check("int32 x", 6, "expected end of input, found `x`");
+        check("[int32; 3", 9, "expected `]`, found end of input");
+        check("[int32; x]", 8, "expected a number, found `x`");
+        check(
+            "string (len 3, padded)",
+            15,
+            "expected `null-terminated`, `null-padded` or `space-padded`, found `padded`",
+        );
+        let enum_hint = "enum members are missing; only the alternate (`{:#}`) form of a type \
+                         includes them";
+        check("enum (int32)", 12, enum_hint);
+        check("[enum (uint8); 2]", 13, enum_hint);
+        check(
+            "enum (float32) {A = 1}",
+            6,
+            "enum base type must be an integer type, found `float32`",
+        );
+        check("enum (int8) {A = 1, B = 128}", 24, "value out of range for `int8`");
+        check("enum (uint8) {A = -1}", 18, "value out of range for `uint8`");
+        check("enum (int8) {A = 1, A = 2}", 20, "duplicate enum member `A`");
+        check(
+            "compound (2 fields)",
+            10,
+            "compound fields are missing; only the alternate (`{:#}`) form of a type includes them",
+        );
+        check("compound (2 bytes)", 10, "expected `size`, found `2`");
+        check("compound {}", 10, "compound type must have at least one field");
+        check("compound {a: int8, a: int8}", 19, "duplicate compound field `a`");
+        check(
+            "compound {a @ 0: int8, b: int8}",
+            23,
+            "either all or none of the compound fields must have offsets",
+        );
+        check("compound {a @ 0: int32, b @ 2: int8}", 24, "compound fields `a` and `b` overlap");
+        check("compound {b @ 2: int8, a @ 0: int32}", 10, "compound fields `a` and `b` overlap");
+        check("compound (size 2) {a: int32}", 19, "compound size 2 is less than required 4");
+        check("opaque (len 2, tag \"abc)", 24, "unterminated string");
+        assert_eq!(
+            parse_err("[bool; 3").to_string(),
+            "invalid type descriptor at position 8: expected `]`, found end of input"
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use std::fmt;
+use std::os::raw::c_void;
+
+use hdf5_sys::h5r::{hdset_reg_ref_t, hobj_ref_t};
+
+/// A reference to a named object (group, dataset or named datatype) in an HDF5 file.
+///
+/// This is the classic object reference (`hobj_ref_t`) which stores the object's address
+/// within its file; as such, it can only be resolved relative to the file it was created in.
+/// A zero reference is considered null (this matches the default value written by HDF5).
+#[repr(transparent)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ObjectReference(hobj_ref_t);
+
+impl ObjectReference {
+    /// Creates a null reference.
+    #[inline]
+    pub const fn null() -> Self {
+        Self(0)
+    }
+
+    /// Returns `true` if this reference doesn't point to any object.
+    #[inline]
+    pub const fn is_null(&self) -> bool {
+        self.0 == 0
+    }
+
+    #[inline]
+    pub fn as_ptr(&self) -> *const c_void {
+        (&self.0 as *const hobj_ref_t).cast()
+    }
+
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut c_void {
+        (&mut self.0 as *mut hobj_ref_t).cast()
+    }
+}
+
+impl fmt::Debug for ObjectReference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_null() {
+            f.write_str("ObjectReference(null)")
+        } else {
+            write!(f, "ObjectReference({:#x})", self.0)
+        }
+    }
+}
+
+/// A reference to a selected region of a dataset in an HDF5 file.
+///
+/// This is the classic dataset region reference (`hdset_reg_ref_t`), which is what most other
+/// tools (e.g. h5py) write; the selection itself is stored in the file's global heap, so the
+/// reference can only be resolved relative to the file it was created in. A zeroed reference
+/// is considered null.
+#[repr(transparent)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct RegionReference(hdset_reg_ref_t);
+
+impl RegionReference {
+    /// Creates a null reference.
+    #[inline]
+    pub const fn null() -> Self {
+        Self([0; 12])
+    }
+
+    /// Returns `true` if this reference doesn't point to any region.
+    #[inline]
+    pub fn is_null(&self) -> bool {
+        self.0.iter().all(|&b| b == 0)
+    }
+
+    #[inline]
+    pub fn as_ptr(&self) -> *const c_void {
+        self.0.as_ptr().cast()
+    }
+
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut c_void {
+        self.0.as_mut_ptr().cast()
+    }
+}
+
+impl fmt::Debug for RegionReference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_null() {
+            f.write_str("RegionReference(null)")
+        } else {
+            f.write_str("RegionReference(0x")?;
+            for b in &self.0 {
+                write!(f, "{:02x}", b)?;
+            }
+            f.write_str(")")
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::mem;
+
+    use hdf5_sys::h5r::{hdset_reg_ref_t, hobj_ref_t};
+
+    use super::{ObjectReference, RegionReference};
+
+    #[test]
+    pub fn test_object_reference() {
+        assert_eq!(mem::size_of::<ObjectReference>(), mem::size_of::<hobj_ref_t>());
+        assert!(ObjectReference::null().is_null());
+        assert!(ObjectReference::default().is_null());
+        assert_eq!(format!("{:?}", ObjectReference::null()), "ObjectReference(null)");
+        assert_eq!(format!("{:?}", ObjectReference(0x2a)), "ObjectReference(0x2a)");
+    }
+
+    #[test]
+    pub fn test_region_reference() {
+        assert_eq!(mem::size_of::<RegionReference>(), mem::size_of::<hdset_reg_ref_t>());
+        assert!(RegionReference::null().is_null());
+        assert!(RegionReference::default().is_null());
+        assert_eq!(format!("{:?}", RegionReference::null()), "RegionReference(null)");
+        let mut bytes = [0; 12];
+        bytes[0] = 0xab;
+        bytes[11] = 0x01;
+        assert!(!RegionReference(bytes).is_null());
+        assert_eq!(
+            format!("{:?}", RegionReference(bytes)),
+            "RegionReference(0xab0000000000000000000001)"
+        );
+    }
+}
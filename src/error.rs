@@ -13,6 +13,7 @@ use hdf5_sys::h5e::{
     H5E_auto2_t, H5E_error2_t, H5Eget_current_stack, H5Eget_msg, H5Eprint2, H5Eset_auto2, H5Ewalk2,
     H5E_DEFAULT, H5E_WALK_DOWNWARD,
 };
+use hdf5_types::{DynValueError, ParseTypeError};
 
 use crate::internal_prelude::*;
 
@@ -268,6 +269,18 @@ impl From<ShapeError> for Error {
     }
 }
 
+impl From<DynValueError> for Error {
+    fn from(err: DynValueError) -> Self {
+        format!("dynamic value error: {}", err).into()
+    }
+}
+
+impl From<ParseTypeError> for Error {
+    fn from(err: ParseTypeError) -> Self {
+        err.to_string().into()
+    }
+}
+
 pub fn h5check<T: H5ErrorCode>(value: T) -> Result<T> {
     H5ErrorCode::h5check(value)
 }
@@ -1,5 +1,6 @@
 pub mod attribute;
 pub mod container;
+mod conversion;
 pub mod dataset;
 pub mod dataspace;
 pub mod datatype;
@@ -10,12 +11,13 @@ pub mod group;
 pub mod location;
 pub mod object;
 pub mod plist;
+pub mod references;
 pub mod selection;
 
 pub use self::{
     attribute::{
         Attribute, AttributeBuilder, AttributeBuilderData, AttributeBuilderEmpty,
-        AttributeBuilderEmptyShape,
+        AttributeBuilderEmptyShape, H5Attrs,
     },
     container::{Container, Reader, Writer},
     dataset::{
@@ -24,7 +26,7 @@ pub use self::{
     dataspace::Dataspace,
     datatype::{Conversion, Datatype},
     file::{File, FileBuilder, OpenMode},
-    group::{Group, LinkInfo, LinkType},
+    group::{Group, H5Group, LinkInfo, LinkType},
     location::{Location, LocationInfo, LocationToken, LocationType},
     object::Object,
     plist::PropertyList,
@@ -8,6 +8,7 @@ use hdf5_sys::{
 use hdf5_types::TypeDescriptor;
 use ndarray::ArrayView;
 
+use crate::hl::datatype::ByteOrder;
 use crate::internal_prelude::*;
 
 /// Represents the HDF5 attribute object.
@@ -127,6 +128,22 @@ impl AttributeBuilder {
         self.builder.packed(packed);
         self
     }
+
+    #[inline]
+    #[must_use]
+    pub fn byte_order(mut self, byte_order: ByteOrder) -> Self {
+        self.builder.byte_order(byte_order);
+        self
+    }
+
+    /// Use a named datatype (see `Group::commit_datatype()`) as the file datatype;
+    /// `packed` and `byte_order` have no effect in this case.
+    #[inline]
+    #[must_use]
+    pub fn committed_type(mut self, dtype: &Datatype) -> Self {
+        self.builder.committed_type(dtype);
+        self
+    }
 }
 
 #[derive(Clone)]
@@ -154,6 +171,22 @@ impl AttributeBuilderEmpty {
         self.builder.packed(packed);
         self
     }
+
+    #[inline]
+    #[must_use]
+    pub fn byte_order(mut self, byte_order: ByteOrder) -> Self {
+        self.builder.byte_order(byte_order);
+        self
+    }
+
+    /// Use a named datatype (see `Group::commit_datatype()`) as the file datatype;
+    /// `packed` and `byte_order` have no effect in this case.
+    #[inline]
+    #[must_use]
+    pub fn committed_type(mut self, dtype: &Datatype) -> Self {
+        self.builder.committed_type(dtype);
+        self
+    }
 }
 
 #[derive(Clone)]
@@ -175,6 +208,22 @@ impl AttributeBuilderEmptyShape {
         self.builder.packed(packed);
         self
     }
+
+    #[inline]
+    #[must_use]
+    pub fn byte_order(mut self, byte_order: ByteOrder) -> Self {
+        self.builder.byte_order(byte_order);
+        self
+    }
+
+    /// Use a named datatype (see `Group::commit_datatype()`) as the file datatype;
+    /// `packed` and `byte_order` have no effect in this case.
+    #[inline]
+    #[must_use]
+    pub fn committed_type(mut self, dtype: &Datatype) -> Self {
+        self.builder.committed_type(dtype);
+        self
+    }
 }
 
 #[derive(Clone)]
@@ -231,6 +280,22 @@ where
         self.builder.packed(packed);
         self
     }
+
+    #[inline]
+    #[must_use]
+    pub fn byte_order(mut self, byte_order: ByteOrder) -> Self {
+        self.builder.byte_order(byte_order);
+        self
+    }
+
+    /// Use a named datatype (see `Group::commit_datatype()`) as the file datatype;
+    /// `packed` and `byte_order` have no effect in this case.
+    #[inline]
+    #[must_use]
+    pub fn committed_type(mut self, dtype: &Datatype) -> Self {
+        self.builder.committed_type(dtype);
+        self
+    }
 }
 
 #[derive(Clone)]
@@ -238,24 +303,42 @@ where
 struct AttributeBuilderInner {
     parent: Result<Handle>,
     packed: bool,
+    byte_order: ByteOrder,
+    committed_type: Option<Datatype>,
 }
 
 impl AttributeBuilderInner {
     pub fn new(parent: &Location) -> Self {
-        Self { parent: parent.try_borrow(), packed: false }
+        Self {
+            parent: parent.try_borrow(),
+            packed: false,
+            byte_order: ByteOrder::native(),
+            committed_type: None,
+        }
     }
 
     pub fn packed(&mut self, packed: bool) {
         self.packed = packed;
     }
 
+    pub fn byte_order(&mut self, byte_order: ByteOrder) {
+        self.byte_order = byte_order;
+    }
+
+    pub fn committed_type(&mut self, dtype: &Datatype) {
+        self.committed_type = Some(dtype.clone());
+    }
+
     unsafe fn create(
         &self, desc: &TypeDescriptor, name: &str, extents: &Extents,
     ) -> Result<Attribute> {
         // construct in-file type descriptor; convert to packed representation if needed
         let desc = if self.packed { desc.to_packed_repr() } else { desc.to_c_repr() };
 
-        let datatype = Datatype::from_descriptor(&desc)?;
+        let datatype = match self.committed_type {
+            Some(ref dtype) => dtype.ensure_committed_for(&desc).map(|_| dtype.clone())?,
+            None => Datatype::from_descriptor_with_order(&desc, self.byte_order)?,
+        };
         let parent = try_ref_clone!(self.parent);
 
         let dataspace = Dataspace::try_new(extents)?;
@@ -281,6 +364,43 @@ impl AttributeBuilderInner {
     }
 }
 
+/// A type whose fields map onto a set of scalar attributes of an object.
+///
+/// This is typically derived via `#[derive(H5Attrs)]`: each field is stored as an attribute
+/// named after the field (or the name given via `#[hdf5(rename = "...")]`), and fields of
+/// type `Option<T>` are optional (the attribute is omitted when writing `None` and read back
+/// as `None` if missing).
+pub trait H5Attrs: Sized {
+    /// Writes all fields as attributes, replacing the existing ones with the same names.
+    fn write_attrs(&self, loc: &Location) -> Result<()>;
+
+    /// Reads the fields back from the attributes.
+    fn read_attrs(loc: &Location) -> Result<Self>;
+}
+
+#[doc(hidden)]
+pub fn write_attr_field<T: H5Type>(loc: &Location, name: &str, value: Option<&T>) -> Result<()> {
+    if loc.attr_exists(name) {
+        loc.delete_attr(name)?;
+    }
+    if let Some(value) = value {
+        loc.new_attr::<T>().create(name)?.write_scalar(value)?;
+    }
+    Ok(())
+}
+
+#[doc(hidden)]
+pub fn read_attr_field<T: H5Type>(loc: &Location, name: &str, optional: bool) -> Result<Option<T>> {
+    if !loc.attr_exists(name) {
+        ensure!(optional, "Attribute {:?} not found", name);
+        return Ok(None);
+    }
+    loc.attr(name)?
+        .read_scalar()
+        .map(Some)
+        .map_err(|err| format!("Attribute {:?}: {}", name, err).into())
+}
+
 #[cfg(test)]
 pub mod attribute_tests {
     use crate::internal_prelude::*;
@@ -8,9 +8,15 @@ use ndarray::{Array, Array1, Array2, ArrayD, ArrayView, ArrayView1};
 use hdf5_sys::h5a::{H5Aget_space, H5Aget_storage_size, H5Aget_type, H5Aread, H5Awrite};
 use hdf5_sys::h5d::{H5Dget_space, H5Dget_storage_size, H5Dget_type, H5Dread, H5Dwrite};
 use hdf5_sys::h5p::H5Pcreate;
+use hdf5_types::{DynValue, OwnedDynValue, TypeDescriptor};
 
 use crate::internal_prelude::*;
 
+/// Allocates a zeroed, pointer-aligned buffer of at least `len` bytes.
+fn alloc_aligned_buf(len: usize) -> Vec<usize> {
+    vec![0; (len + mem::size_of::<usize>() - 1) / mem::size_of::<usize>()]
+}
+
 #[derive(Debug)]
 pub struct Reader<'a> {
     obj: &'a Container,
@@ -38,11 +44,57 @@ impl<'a> Reader<'a> {
     }
 
     fn read_into_buf<T: H5Type>(
-        &self, buf: *mut T, fspace: Option<&Dataspace>, mspace: Option<&Dataspace>,
+        &self, buf: *mut T, len: usize, fspace: Option<&Dataspace>, mspace: Option<&Dataspace>,
     ) -> Result<()> {
+        let missing = self.missing_default_fields::<T>()?;
+        if missing.is_empty() {
+            let mem_dtype = Datatype::from_type::<T>()?;
+            return self.read_into_raw_buf(buf.cast(), &mem_dtype, fspace, mspace);
+        }
+        // read the fields present in the file in place (the memory layout of the remaining
+        // fields is unchanged), then fill in the defaults for the missing ones
+        if let TypeDescriptor::Compound(mut compound) = T::type_descriptor() {
+            compound.fields.retain(|f| !missing.contains(&f.name.as_str()));
+            for (i, field) in compound.fields.iter_mut().enumerate() {
+                field.index = i;
+            }
+            if !compound.fields.is_empty() {
+                let mem_dtype = Datatype::from_descriptor(&TypeDescriptor::Compound(compound))?;
+                self.read_into_raw_buf(buf.cast(), &mem_dtype, fspace, mspace)?;
+            }
+        }
+        for i in 0..len {
+            for name in &missing {
+                unsafe { T::write_default_field(buf.add(i), name) };
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the fields of `T` with default values that are missing from the file datatype.
+    fn missing_default_fields<T: H5Type>(&self) -> Result<Vec<&'static str>> {
+        if T::default_fields().is_empty() {
+            return Ok(vec![]);
+        }
         let file_dtype = self.obj.dtype()?;
-        let mem_dtype = Datatype::from_type::<T>()?;
-        file_dtype.ensure_convertible(&mem_dtype, self.conv)?;
+        if !file_dtype.is_compound() {
+            return Ok(vec![]);
+        }
+        let file_members = file_dtype.compound_members()?;
+        Ok(T::default_fields()
+            .iter()
+            .copied()
+            .filter(|&name| file_members.iter().all(|(member, _)| member != name))
+            .collect())
+    }
+
+    fn read_into_raw_buf(
+        &self, buf: *mut u8, mem_dtype: &Datatype, fspace: Option<&Dataspace>,
+        mspace: Option<&Dataspace>,
+    ) -> Result<()> {
+        let file_dtype = self.obj.dtype()?;
+        let mem_dtype = mem_dtype.with_array_layout_of(&file_dtype)?;
+        file_dtype.ensure_convertible_by_name(&mem_dtype, self.conv)?;
         let (obj_id, tp_id) = (self.obj.id(), mem_dtype.id());
 
         if self.obj.is_attr() {
@@ -101,7 +153,7 @@ impl<'a> Reader<'a> {
         } else {
             let mspace = Dataspace::try_new(&out_shape)?;
             let mut buf = Vec::with_capacity(out_size);
-            self.read_into_buf(buf.as_mut_ptr(), Some(&fspace), Some(&mspace))?;
+            self.read_into_buf(buf.as_mut_ptr(), out_size, Some(&fspace), Some(&mspace))?;
             unsafe {
                 buf.set_len(out_size);
             };
@@ -129,7 +181,7 @@ impl<'a> Reader<'a> {
     pub fn read_raw<T: H5Type>(&self) -> Result<Vec<T>> {
         let size = self.obj.space()?.size();
         let mut vec = Vec::with_capacity(size);
-        self.read_into_buf(vec.as_mut_ptr(), None, None).map(|_| {
+        self.read_into_buf(vec.as_mut_ptr(), size, None, None).map(|_| {
             unsafe {
                 vec.set_len(size);
             };
@@ -183,7 +235,173 @@ impl<'a> Reader<'a> {
         let obj_ndim = self.obj.get_shape()?.ndim();
         ensure!(obj_ndim == 0, "ndim mismatch: expected scalar, got {}", obj_ndim);
         let mut val = mem::MaybeUninit::<T>::uninit();
-        self.read_into_buf(val.as_mut_ptr(), None, None).map(|_| unsafe { val.assume_init() })
+        self.read_into_buf(val.as_mut_ptr(), 1, None, None).map(|_| unsafe { val.assume_init() })
+    }
+
+    fn read_dyn_values_as(
+        &self, desc: &TypeDescriptor, len: usize, fspace: Option<&Dataspace>,
+        mspace: Option<&Dataspace>,
+    ) -> Result<Vec<OwnedDynValue>> {
+        // the file layout may be packed, so fields are read into their aligned C layout
+        let desc = desc.to_c_repr();
+        let mem_dtype = Datatype::from_descriptor(&desc)?;
+        let size = desc.size();
+
+        // usize-aligned so that variable-length data pointers can be accessed in place
+        let mut buf = alloc_aligned_buf(len * size);
+        self.read_into_raw_buf(buf.as_mut_ptr().cast(), &mem_dtype, fspace, mspace)?;
+        let bytes = unsafe { std::slice::from_raw_parts(buf.as_ptr().cast::<u8>(), len * size) };
+
+        // each value takes ownership of its variable-length data and frees it when dropped
+        Ok(bytes
+            .chunks_exact(size)
+            .map(|chunk| unsafe { OwnedDynValue::from_raw(desc.clone(), chunk.into()) })
+            .collect())
+    }
+
+    fn read_dyn_values_into_vec(
+        &self, len: usize, fspace: Option<&Dataspace>, mspace: Option<&Dataspace>,
+    ) -> Result<Vec<OwnedDynValue>> {
+        let desc = self.obj.dtype()?.to_descriptor()?;
+        self.read_dyn_values_as(&desc, len, fspace, mspace)
+    }
+
+    fn read_strings_into_vec(
+        &self, len: usize, fspace: Option<&Dataspace>, mspace: Option<&Dataspace>,
+    ) -> Result<Vec<String>> {
+        let desc = self.obj.dtype()?.to_descriptor()?;
+        match desc {
+            TypeDescriptor::FixedAscii(..)
+            | TypeDescriptor::FixedUnicode(..)
+            | TypeDescriptor::VarLenAscii
+            | TypeDescriptor::VarLenUnicode => {}
+            _ => fail!("Expected a string datatype, got {}", desc),
+        }
+        self.read_dyn_values_as(&desc, len, fspace, mspace)?
+            .iter()
+            .map(|value| match value.get() {
+                DynValue::String(s) => s.as_str().map(ToOwned::to_owned),
+                _ => None,
+            })
+            .collect::<Option<_>>()
+            .ok_or_else(|| "Invalid UTF-8 in string dataset/attribute".into())
+    }
+
+    fn read_field_into_vec<T: H5Type>(
+        &self, name: &str, len: usize, fspace: Option<&Dataspace>, mspace: Option<&Dataspace>,
+    ) -> Result<Vec<T>> {
+        let file_dtype = self.obj.dtype()?;
+        ensure!(
+            file_dtype.is_compound(),
+            "Cannot read field {:?} of a non-compound datatype",
+            name
+        );
+
+        // only the requested member is transferred and converted
+        let mem_dtype = Datatype::single_member_compound(name, &Datatype::from_type::<T>()?)?;
+        let mut vec = Vec::<T>::with_capacity(len);
+        self.read_into_raw_buf(vec.as_mut_ptr().cast(), &mem_dtype, fspace, mspace)?;
+        unsafe {
+            vec.set_len(len);
+        }
+        Ok(vec)
+    }
+
+    fn read_slice_with<T, S, F>(&self, selection: S, read_vec: F) -> Result<ArrayD<T>>
+    where
+        S: TryInto<Selection>,
+        Error: From<S::Error>,
+        F: Fn(usize, Option<&Dataspace>, Option<&Dataspace>) -> Result<Vec<T>>,
+    {
+        ensure!(!self.obj.is_attr(), "Slicing cannot be used on attribute datasets");
+
+        let selection = selection.try_into()?;
+        let obj_space = self.obj.space()?;
+        let out_shape = selection.out_shape(&obj_space.shape())?;
+        let out_size: Ix = out_shape.iter().product();
+
+        if out_size == 0 {
+            Ok(ArrayD::from_shape_vec(out_shape, vec![])?)
+        } else if obj_space.ndim() == 0 {
+            Ok(ArrayD::from_shape_vec(obj_space.shape(), read_vec(1, None, None)?)?)
+        } else {
+            let fspace = obj_space.select(selection)?;
+            let mspace = Dataspace::try_new(&out_shape)?;
+            let vec = read_vec(out_size, Some(&fspace), Some(&mspace))?;
+            Ok(ArrayD::from_shape_vec(out_shape, vec)?)
+        }
+    }
+
+    /// Reads a string dataset/attribute into an array of owned strings.
+    ///
+    /// The string type is determined from the file datatype, so both fixed-length and
+    /// variable-length strings are supported, either ASCII or UTF-8.
+    pub fn read_strings(&self) -> Result<ArrayD<String>> {
+        let shape = self.obj.get_shape()?;
+        let vec = self.read_strings_into_vec(shape.size(), None, None)?;
+        Ok(ArrayD::from_shape_vec(shape, vec)?)
+    }
+
+    /// Reads the given `slice` of a string dataset into an array of owned strings.
+    ///
+    /// See [`read_strings`](Self::read_strings) for the supported string types.
+    pub fn read_strings_slice<S>(&self, selection: S) -> Result<ArrayD<String>>
+    where
+        S: TryInto<Selection>,
+        Error: From<S::Error>,
+    {
+        self.read_slice_with(selection, |len, fspace, mspace| {
+            self.read_strings_into_vec(len, fspace, mspace)
+        })
+    }
+
+    /// Reads a single field of a compound dataset/attribute into an array.
+    ///
+    /// The field is looked up by name in the file datatype and converted to `T`; the other
+    /// fields are not transferred. To read several fields at once, read into a struct whose
+    /// fields are a subset of the compound fields instead (they are matched by name).
+    pub fn read_field<T: H5Type>(&self, name: &str) -> Result<ArrayD<T>> {
+        let shape = self.obj.get_shape()?;
+        let vec = self.read_field_into_vec(name, shape.size(), None, None)?;
+        Ok(ArrayD::from_shape_vec(shape, vec)?)
+    }
+
+    /// Reads a single field of the given `slice` of a compound dataset into an array.
+    ///
+    /// See [`read_field`](Self::read_field) for details.
+    pub fn read_field_slice<T, S>(&self, name: &str, selection: S) -> Result<ArrayD<T>>
+    where
+        T: H5Type,
+        S: TryInto<Selection>,
+        Error: From<S::Error>,
+    {
+        self.read_slice_with(selection, |len, fspace, mspace| {
+            self.read_field_into_vec(name, len, fspace, mspace)
+        })
+    }
+
+    /// Reads a dataset/attribute of any type into an array of dynamic values.
+    ///
+    /// The memory type is derived from the file datatype via [`Datatype::to_descriptor`],
+    /// so the type doesn't have to be known in advance. Any variable-length data is owned
+    /// by the returned values and freed when they are dropped.
+    pub fn read_dyn_values(&self) -> Result<ArrayD<OwnedDynValue>> {
+        let shape = self.obj.get_shape()?;
+        let vec = self.read_dyn_values_into_vec(shape.size(), None, None)?;
+        Ok(ArrayD::from_shape_vec(shape, vec)?)
+    }
+
+    /// Reads the given `slice` of a dataset of any type into an array of dynamic values.
+    ///
+    /// See [`read_dyn_values`](Self::read_dyn_values) for details.
+    pub fn read_dyn_values_slice<S>(&self, selection: S) -> Result<ArrayD<OwnedDynValue>>
+    where
+        S: TryInto<Selection>,
+        Error: From<S::Error>,
+    {
+        self.read_slice_with(selection, |len, fspace, mspace| {
+            self.read_dyn_values_into_vec(len, fspace, mspace)
+        })
     }
 }
 
@@ -216,8 +434,16 @@ impl<'a> Writer<'a> {
     fn write_from_buf<T: H5Type>(
         &self, buf: *const T, fspace: Option<&Dataspace>, mspace: Option<&Dataspace>,
     ) -> Result<()> {
-        let file_dtype = self.obj.dtype()?;
         let mem_dtype = Datatype::from_type::<T>()?;
+        self.write_from_raw_buf(buf.cast(), &mem_dtype, fspace, mspace)
+    }
+
+    fn write_from_raw_buf(
+        &self, buf: *const u8, mem_dtype: &Datatype, fspace: Option<&Dataspace>,
+        mspace: Option<&Dataspace>,
+    ) -> Result<()> {
+        let file_dtype = self.obj.dtype()?;
+        let mem_dtype = mem_dtype.with_array_layout_of(&file_dtype)?;
         mem_dtype.ensure_convertible(&file_dtype, self.conv)?;
         let (obj_id, tp_id) = (self.obj.id(), mem_dtype.id());
 
@@ -338,6 +564,45 @@ impl<'a> Writer<'a> {
         self.write_from_buf(view.as_ptr(), None, None)
     }
 
+    /// Writes dynamic values into a dataset/attribute in memory order.
+    ///
+    /// All values must have the same type descriptor, which is used as the memory type
+    /// and must be convertible to the datatype of the dataset/attribute. The number of
+    /// values must match the number of elements in the destination dataset/attribute.
+    pub fn write_dyn_values(&self, values: &[OwnedDynValue]) -> Result<()> {
+        let dst = self.obj.get_shape()?.size();
+        if values.len() != dst {
+            fail!(
+                "length mismatch when writing: memory = {:?}, destination = {:?}",
+                values.len(),
+                dst
+            );
+        }
+        let tp = match values.first() {
+            Some(value) => value.type_descriptor(),
+            None => return Ok(()),
+        };
+        if let Some(value) = values.iter().find(|value| value.type_descriptor() != tp) {
+            fail!(
+                "type mismatch when writing dynamic values: {} != {}",
+                value.type_descriptor(),
+                tp
+            );
+        }
+        let mem_dtype = Datatype::from_descriptor(tp)?;
+        let size = tp.size();
+
+        // usize-aligned copy of the values; nested allocations are still owned by `values`
+        let mut buf = alloc_aligned_buf(values.len() * size);
+        let bytes = unsafe {
+            std::slice::from_raw_parts_mut(buf.as_mut_ptr().cast::<u8>(), values.len() * size)
+        };
+        for (chunk, value) in bytes.chunks_exact_mut(size).zip(values) {
+            chunk.copy_from_slice(unsafe { value.get_buf() });
+        }
+        self.write_from_raw_buf(bytes.as_ptr(), &mem_dtype, None, None)
+    }
+
     /// Writes a scalar dataset/attribute.
     pub fn write_scalar<T: H5Type>(&self, val: &T) -> Result<()> {
         let ndim = self.obj.get_shape()?.ndim();
@@ -522,6 +787,49 @@ impl Container {
         self.as_reader().read_scalar()
     }
 
+    /// Reads a string dataset/attribute of any string type into an array of owned strings.
+    pub fn read_strings(&self) -> Result<ArrayD<String>> {
+        self.as_reader().read_strings()
+    }
+
+    /// Reads the given `slice` of a string dataset into an array of owned strings.
+    pub fn read_strings_slice<S>(&self, selection: S) -> Result<ArrayD<String>>
+    where
+        S: TryInto<Selection>,
+        Error: From<S::Error>,
+    {
+        self.as_reader().read_strings_slice(selection)
+    }
+
+    /// Reads a single field of a compound dataset/attribute into an array.
+    pub fn read_field<T: H5Type>(&self, name: &str) -> Result<ArrayD<T>> {
+        self.as_reader().read_field(name)
+    }
+
+    /// Reads a single field of the given `slice` of a compound dataset into an array.
+    pub fn read_field_slice<T, S>(&self, name: &str, selection: S) -> Result<ArrayD<T>>
+    where
+        T: H5Type,
+        S: TryInto<Selection>,
+        Error: From<S::Error>,
+    {
+        self.as_reader().read_field_slice(name, selection)
+    }
+
+    /// Reads a dataset/attribute of any type into an array of dynamic values.
+    pub fn read_dyn_values(&self) -> Result<ArrayD<OwnedDynValue>> {
+        self.as_reader().read_dyn_values()
+    }
+
+    /// Reads the given `slice` of a dataset of any type into an array of dynamic values.
+    pub fn read_dyn_values_slice<S>(&self, selection: S) -> Result<ArrayD<OwnedDynValue>>
+    where
+        S: TryInto<Selection>,
+        Error: From<S::Error>,
+    {
+        self.as_reader().read_dyn_values_slice(selection)
+    }
+
     /// Writes an n-dimensional array view into a dataset/attribute.
     ///
     /// The shape of the view must match the shape of the dataset/attribute exactly.
@@ -548,6 +856,13 @@ impl Container {
         self.as_writer().write_raw(arr)
     }
 
+    /// Writes dynamic values into a dataset/attribute in memory order.
+    ///
+    /// See [`Writer::write_dyn_values`] for details.
+    pub fn write_dyn_values(&self, values: &[OwnedDynValue]) -> Result<()> {
+        self.as_writer().write_dyn_values(values)
+    }
+
     /// Writes all data from the array `arr` into the given `slice` of the target dataset.
     /// The shape of `arr` must match the shape the set of elements included in the slice.
     /// If the array has a fixed number of dimensions, it must match the dimensionality of
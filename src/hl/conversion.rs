@@ -0,0 +1,172 @@
+use std::panic;
+use std::ptr;
+use std::slice;
+use std::sync::Arc;
+
+use lazy_static::lazy_static;
+use parking_lot::Mutex;
+
+use hdf5_sys::h5t::{
+    H5T_cdata_t, H5T_cmd_t, H5T_conv_t, H5T_pers_t, H5Tclose, H5Tcopy, H5Tequal, H5Tget_size,
+    H5Tregister, H5Tunregister,
+};
+
+use crate::internal_prelude::*;
+
+type ConversionFn = dyn Fn(&[u8], &mut [u8]) + Send + Sync;
+
+/// A registered user-defined conversion; owns copies of the source and destination types.
+struct UserConversion {
+    src_id: hid_t,
+    dst_id: hid_t,
+    func: Arc<ConversionFn>,
+}
+
+// the datatype ids are only ever accessed while holding the library lock
+unsafe impl Send for UserConversion {}
+
+impl UserConversion {
+    fn matches(&self, src_id: hid_t, dst_id: hid_t) -> bool {
+        unsafe { H5Tequal(self.src_id, src_id) > 0 && H5Tequal(self.dst_id, dst_id) > 0 }
+    }
+}
+
+impl Drop for UserConversion {
+    fn drop(&mut self) {
+        unsafe {
+            H5Tclose(self.src_id);
+            H5Tclose(self.dst_id);
+        }
+    }
+}
+
+lazy_static! {
+    static ref USER_CONVERSIONS: Mutex<Vec<UserConversion>> = Mutex::new(Vec::new());
+}
+
+/// Per-path private data, stored in `H5T_cdata_t` between the init and free commands.
+struct ConversionData {
+    func: Arc<ConversionFn>,
+    src_size: usize,
+    dst_size: usize,
+}
+
+unsafe fn convert_elements(data: &ConversionData, nelmts: usize, buf_stride: usize, buf: *mut u8) {
+    let (src_size, dst_size) = (data.src_size, data.dst_size);
+    let (src_stride, dst_stride) =
+        if buf_stride == 0 { (src_size, dst_size) } else { (buf_stride, buf_stride) };
+    let mut src = vec![0_u8; src_size];
+    let mut convert = |i: usize| {
+        ptr::copy_nonoverlapping(buf.add(i * src_stride), src.as_mut_ptr(), src_size);
+        let dst = slice::from_raw_parts_mut(buf.add(i * dst_stride), dst_size);
+        dst.fill(0);
+        (data.func)(&src, dst);
+    };
+    // conversion is done in place, so the order must not clobber unconverted elements
+    if dst_stride > src_stride {
+        (0..nelmts).rev().for_each(&mut convert);
+    } else {
+        (0..nelmts).for_each(&mut convert);
+    }
+}
+
+extern "C" fn convert_user(
+    src_id: hid_t, dst_id: hid_t, cdata: *mut H5T_cdata_t, nelmts: size_t, buf_stride: size_t,
+    _bkg_stride: size_t, buf: *mut c_void, _bkg: *mut c_void, _dxpl: hid_t,
+) -> herr_t {
+    panic::catch_unwind(|| unsafe {
+        let cdata = &mut *cdata;
+        match cdata.command {
+            H5T_cmd_t::H5T_CONV_INIT => {
+                let conversions = USER_CONVERSIONS.lock();
+                let conversion = conversions.iter().rev().find(|c| c.matches(src_id, dst_id));
+                if let Some(conversion) = conversion {
+                    let data = ConversionData {
+                        func: Arc::clone(&conversion.func),
+                        src_size: H5Tget_size(src_id),
+                        dst_size: H5Tget_size(dst_id),
+                    };
+                    cdata._priv = Box::into_raw(Box::new(data)).cast();
+                    0
+                } else {
+                    -1
+                }
+            }
+            H5T_cmd_t::H5T_CONV_CONV => {
+                let data = cdata._priv.cast::<ConversionData>();
+                if data.is_null() {
+                    return -1;
+                }
+                if nelmts > 0 && !buf.is_null() {
+                    convert_elements(&*data, nelmts as _, buf_stride as _, buf.cast());
+                }
+                0
+            }
+            H5T_cmd_t::H5T_CONV_FREE => {
+                let data = cdata._priv.cast::<ConversionData>();
+                if !data.is_null() {
+                    drop(Box::from_raw(data));
+                    cdata._priv = ptr::null_mut();
+                }
+                0
+            }
+        }
+    })
+    .unwrap_or(-1)
+}
+
+const CONVERT_USER: H5T_conv_t = Some(convert_user);
+
+impl Datatype {
+    /// Registers a user-defined function converting elements of type `src` to type `dst`.
+    ///
+    /// The function is called for each element with the bytes of the source element and
+    /// the (zeroed) bytes of the destination element it has to fill in. It only applies to
+    /// this exact pair of datatypes and is registered as a soft conversion: it takes
+    /// precedence over the library's soft conversions, but not over its hard (compiled)
+    /// conversions between native types. Readers and writers use it transparently if soft
+    /// conversions are allowed. Registering a function for the same pair of types again
+    /// replaces the previous one.
+    pub fn register_conversion<F>(name: &str, src: &Self, dst: &Self, func: F) -> Result<()>
+    where
+        F: Fn(&[u8], &mut [u8]) + Send + Sync + 'static,
+    {
+        let name = to_cstring(name)?;
+        h5lock!({
+            if USER_CONVERSIONS.lock().iter().any(|c| c.matches(src.id(), dst.id())) {
+                Self::unregister_conversion(src, dst)?;
+            }
+            let conversion = UserConversion {
+                src_id: h5try!(H5Tcopy(src.id())),
+                dst_id: h5try!(H5Tcopy(dst.id())),
+                func: Arc::new(func),
+            };
+            USER_CONVERSIONS.lock().push(conversion);
+            let pers = H5T_pers_t::H5T_PERS_SOFT;
+            if let Err(err) =
+                h5call!(H5Tregister(pers, name.as_ptr(), src.id(), dst.id(), CONVERT_USER))
+            {
+                USER_CONVERSIONS.lock().pop();
+                return Err(err);
+            }
+            Ok(())
+        })
+    }
+
+    /// Removes a user-defined conversion from `src` to `dst`, restoring the library default.
+    pub fn unregister_conversion(src: &Self, dst: &Self) -> Result<()> {
+        h5lock!({
+            let pers = H5T_pers_t::H5T_PERS_SOFT;
+            let mut conversions = USER_CONVERSIONS.lock();
+            let count = conversions.len();
+            conversions.retain(|c| !c.matches(src.id(), dst.id()));
+            ensure!(
+                conversions.len() < count,
+                "No user-defined conversion between these datatypes"
+            );
+            drop(conversions);
+            h5try!(H5Tunregister(pers, ptr::null(), src.id(), dst.id(), CONVERT_USER));
+            Ok(())
+        })
+    }
+}
@@ -1,3 +1,4 @@
+use std::any::TypeId;
 use std::fmt::{self, Debug};
 use std::ops::Deref;
 
@@ -13,8 +14,12 @@ use hdf5_sys::h5d::{H5Dget_chunk_info, H5Dget_num_chunks};
 use hdf5_sys::h5l::H5Ldelete;
 use hdf5_sys::h5p::H5P_DEFAULT;
 use hdf5_sys::h5z::H5Z_filter_t;
-use hdf5_types::{OwnedDynValue, TypeDescriptor};
+use hdf5_types::{
+    OwnedDynValue, TimeDelta, Timestamp, TypeDescriptor, VarLenUnicode, TIMESTAMP_UNITS,
+    TIME_DELTA_UNITS,
+};
 
+use crate::hl::datatype::{ByteOrder, Datatype};
 #[cfg(feature = "blosc")]
 use crate::hl::filters::{Blosc, BloscShuffle};
 use crate::hl::filters::{Filter, SZip, ScaleOffset};
@@ -240,7 +245,8 @@ impl DatasetBuilder {
         Self { builder: DatasetBuilderInner::new(parent) }
     }
 
-    pub fn empty<T: H5Type>(self) -> DatasetBuilderEmpty {
+    pub fn empty<T: H5Type>(mut self) -> DatasetBuilderEmpty {
+        self.builder.default_units::<T>();
         self.empty_as(&T::type_descriptor())
     }
 
@@ -258,13 +264,14 @@ impl DatasetBuilder {
     }
 
     pub fn with_data_as<'d, A, T, D>(
-        self, data: A, type_desc: &TypeDescriptor,
+        mut self, data: A, type_desc: &TypeDescriptor,
     ) -> DatasetBuilderData<'d, T, D>
     where
         A: Into<ArrayView<'d, T, D>>,
         T: H5Type,
         D: ndarray::Dimension,
     {
+        self.builder.default_units::<T>();
         DatasetBuilderData {
             builder: self.builder,
             data: data.into(),
@@ -356,6 +363,11 @@ where
     }
 }
 
+fn write_units(ds: &Dataset, units: &str) -> Result<()> {
+    let units: VarLenUnicode = units.parse().map_err(|err| format!("{}", err))?;
+    ds.new_attr::<VarLenUnicode>().create("units")?.write_scalar(&units)
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Chunk {
     Exact(Vec<Ix>), // exact chunk shape
@@ -408,7 +420,10 @@ struct DatasetBuilderInner {
     dcpl_builder: DatasetCreateBuilder,
     lcpl_builder: LinkCreateBuilder,
     packed: bool,
+    byte_order: ByteOrder,
+    committed_type: Option<Datatype>,
     chunk: Option<Chunk>,
+    units: Option<String>,
 }
 
 impl DatasetBuilderInner {
@@ -428,7 +443,10 @@ impl DatasetBuilderInner {
             dcpl_builder: dcpl,
             lcpl_builder: lcpl,
             packed: false,
+            byte_order: ByteOrder::native(),
+            committed_type: None,
             chunk: None,
+            units: None,
         }
     }
 
@@ -436,6 +454,32 @@ impl DatasetBuilderInner {
         self.packed = packed;
     }
 
+    pub fn byte_order(&mut self, byte_order: ByteOrder) {
+        self.byte_order = byte_order;
+    }
+
+    pub fn committed_type(&mut self, dtype: &Datatype) {
+        self.committed_type = Some(dtype.clone());
+    }
+
+    pub fn units(&mut self, units: &str) {
+        self.units = Some(units.into());
+    }
+
+    /// Sets the units of `Timestamp` and `TimeDelta` datasets unless set explicitly.
+    fn default_units<T: 'static>(&mut self) {
+        if self.units.is_none() {
+            let units = if TypeId::of::<T>() == TypeId::of::<Timestamp>() {
+                Some(TIMESTAMP_UNITS)
+            } else if TypeId::of::<T>() == TypeId::of::<TimeDelta>() {
+                Some(TIME_DELTA_UNITS)
+            } else {
+                None
+            };
+            self.units = units.map(Into::into);
+        }
+    }
+
     fn build_dapl(&self) -> Result<DatasetAccess> {
         let mut dapl = match &self.dapl_base {
             Some(dapl) => dapl.clone(),
@@ -530,7 +574,10 @@ impl DatasetBuilderInner {
     ) -> Result<Dataset> {
         // construct in-file type descriptor; convert to packed representation if needed
         let desc = if self.packed { desc.to_packed_repr() } else { desc.to_c_repr() };
-        let dtype = Datatype::from_descriptor(&desc)?;
+        let dtype = match self.committed_type {
+            Some(ref dtype) => dtype.ensure_committed_for(&desc).map(|_| dtype.clone())?,
+            None => Datatype::from_descriptor_with_order(&desc, self.byte_order)?,
+        };
 
         // construct DAPL and DCPL, validate filters
         let dapl = self.build_dapl()?;
@@ -552,7 +599,16 @@ impl DatasetBuilderInner {
             // create anonymous dataset
             H5Dcreate_anon(pid, dtype_id, space_id, dcpl_id, dapl_id)
         };
-        Dataset::from_id(h5check(ds_id)?)
+        let ds = Dataset::from_id(h5check(ds_id)?)?;
+
+        // attach the units, if any
+        if let Some(ref units) = self.units {
+            if let Err(err) = write_units(&ds, units) {
+                self.try_unlink(name);
+                return Err(err);
+            }
+        }
+        Ok(ds)
     }
 
     ////////////////////
@@ -900,7 +956,8 @@ macro_rules! impl_builder {
             }
         }
     };
-    (*: $name:ident($($var:ident: $ty:ty),*)) => {
+    ($(#[$meta:meta])* *: $name:ident($($var:ident: $ty:ty),*)) => {
+        $(#[$meta])*
         #[inline] #[must_use]
         pub fn $name(mut self $(, $var: $ty)*) -> Self {
             self.builder.$name($($var),*); self
@@ -941,6 +998,20 @@ macro_rules! impl_builder {
 macro_rules! impl_builder_methods {
     () => {
         impl_builder!(*: packed(packed: bool));
+        impl_builder!(*: byte_order(byte_order: ByteOrder));
+        impl_builder!(
+            /// Use a named datatype (see `Group::commit_datatype()`) as the file datatype;
+            /// `packed` and `byte_order` have no effect in this case.
+            *: committed_type(dtype: &Datatype)
+        );
+        impl_builder!(
+            /// Store the physical units of the values in the `units` attribute of the dataset.
+            ///
+            /// Datasets whose element type is `Timestamp` or `TimeDelta` get the respective
+            /// units by default (`TIMESTAMP_UNITS` or `TIME_DELTA_UNITS`); this does not apply
+            /// to arrays or compounds containing them, or to datasets created via `empty_as()`.
+            *: units(units: &str)
+        );
 
         impl_builder!(DatasetAccess: access/dapl);
 
@@ -5,42 +5,37 @@ use std::ops::Deref;
 
 use hdf5_sys::h5t::{
     H5T_cdata_t, H5T_class_t, H5T_cset_t, H5T_order_t, H5T_sign_t, H5T_str_t, H5Tarray_create2,
-    H5Tcompiler_conv, H5Tcopy, H5Tcreate, H5Tenum_create, H5Tenum_insert, H5Tequal, H5Tfind,
-    H5Tget_array_dims2, H5Tget_array_ndims, H5Tget_class, H5Tget_cset, H5Tget_member_name,
-    H5Tget_member_offset, H5Tget_member_type, H5Tget_member_value, H5Tget_nmembers, H5Tget_order,
-    H5Tget_sign, H5Tget_size, H5Tget_super, H5Tinsert, H5Tis_variable_str, H5Tset_cset,
-    H5Tset_size, H5Tset_strpad, H5Tvlen_create, H5T_VARIABLE,
+    H5Tcommitted, H5Tcompiler_conv, H5Tcopy, H5Tcreate, H5Tenum_create, H5Tenum_insert, H5Tequal,
+    H5Tfind, H5Tget_array_dims2, H5Tget_array_ndims, H5Tget_class, H5Tget_cset, H5Tget_ebias,
+    H5Tget_fields, H5Tget_member_name, H5Tget_member_offset, H5Tget_member_type,
+    H5Tget_member_value, H5Tget_nmembers, H5Tget_order, H5Tget_precision, H5Tget_sign, H5Tget_size,
+    H5Tget_strpad, H5Tget_super, H5Tget_tag, H5Tinsert, H5Tis_variable_str, H5Tset_cset,
+    H5Tset_ebias, H5Tset_fields, H5Tset_size, H5Tset_strpad, H5Tset_tag, H5Tvlen_create,
+    H5T_VARIABLE,
 };
 use hdf5_types::{
-    CompoundField, CompoundType, EnumMember, EnumType, FloatSize, H5Type, IntSize, TypeDescriptor,
+    CompoundField, CompoundType, EnumMember, EnumType, FloatSize, H5Type, IntSize, Reference,
+    StringPadding, TypeDescriptor,
 };
 
-use crate::globals::{H5T_C_S1, H5T_NATIVE_INT, H5T_NATIVE_INT8};
-use crate::internal_prelude::*;
-
-#[cfg(target_endian = "big")]
+#[cfg(feature = "1.12.0")]
+use crate::globals::H5T_STD_REF;
 use crate::globals::{
-    H5T_IEEE_F32BE, H5T_IEEE_F64BE, H5T_STD_I16BE, H5T_STD_I32BE, H5T_STD_I64BE, H5T_STD_I8BE,
-    H5T_STD_U16BE, H5T_STD_U32BE, H5T_STD_U64BE, H5T_STD_U8BE,
+    H5T_C_S1, H5T_NATIVE_INT, H5T_NATIVE_INT8, H5T_STD_REF_DSETREG, H5T_STD_REF_OBJ,
 };
+use crate::internal_prelude::*;
 
-#[cfg(target_endian = "little")]
 use crate::globals::{
-    H5T_IEEE_F32LE, H5T_IEEE_F64LE, H5T_STD_I16LE, H5T_STD_I32LE, H5T_STD_I64LE, H5T_STD_I8LE,
-    H5T_STD_U16LE, H5T_STD_U32LE, H5T_STD_U64LE, H5T_STD_U8LE,
+    H5T_IEEE_F32BE, H5T_IEEE_F32LE, H5T_IEEE_F64BE, H5T_IEEE_F64LE, H5T_STD_B16BE, H5T_STD_B16LE,
+    H5T_STD_B32BE, H5T_STD_B32LE, H5T_STD_B64BE, H5T_STD_B64LE, H5T_STD_B8BE, H5T_STD_B8LE,
+    H5T_STD_I16BE, H5T_STD_I16LE, H5T_STD_I32BE, H5T_STD_I32LE, H5T_STD_I64BE, H5T_STD_I64LE,
+    H5T_STD_I8BE, H5T_STD_I8LE, H5T_STD_U16BE, H5T_STD_U16LE, H5T_STD_U32BE, H5T_STD_U32LE,
+    H5T_STD_U64BE, H5T_STD_U64LE, H5T_STD_U8BE, H5T_STD_U8LE,
 };
 
-#[cfg(target_endian = "big")]
-macro_rules! be_le {
-    ($be:expr, $le:expr) => {
-        h5try!(H5Tcopy(*$be))
-    };
-}
-
-#[cfg(target_endian = "little")]
 macro_rules! be_le {
-    ($be:expr, $le:expr) => {
-        h5try!(H5Tcopy(*$le))
+    ($order:expr, $be:expr, $le:expr) => {
+        h5try!(H5Tcopy(if $order == ByteOrder::BigEndian { *$be } else { *$le }))
     };
 }
 
@@ -128,6 +123,17 @@ pub enum ByteOrder {
     None,
 }
 
+impl ByteOrder {
+    /// Returns the byte order of the target platform.
+    pub const fn native() -> Self {
+        if cfg!(target_endian = "big") {
+            Self::BigEndian
+        } else {
+            Self::LittleEndian
+        }
+    }
+}
+
 #[cfg(feature = "1.8.6")]
 impl From<H5T_order_t> for ByteOrder {
     fn from(order: H5T_order_t) -> Self {
@@ -164,6 +170,19 @@ impl Datatype {
         h5lock!(H5Tget_order(self.id())).into()
     }
 
+    /// Get the number of significant bits of an atomic datatype.
+    ///
+    /// This may be less than the total size in bits, e.g. for 12-bit integers stored in
+    /// two bytes; returns 0 for non-atomic datatypes.
+    pub fn precision(&self) -> usize {
+        h5lock!(H5Tget_precision(self.id())) as usize
+    }
+
+    /// Returns true if the datatype is committed to a file (that is, a named datatype).
+    pub fn is_committed(&self) -> bool {
+        h5lock!(H5Tcommitted(self.id())) > 0
+    }
+
     pub fn conv_path<D>(&self, dst: D) -> Option<Conversion>
     where
         D: Borrow<Self>,
@@ -172,7 +191,8 @@ impl Datatype {
         let mut cdata = H5T_cdata_t::default();
         h5lock!({
             let noop = H5Tfind(*H5T_NATIVE_INT, *H5T_NATIVE_INT, &mut (&mut cdata as *mut _));
-            if H5Tfind(self.id(), dst.id(), &mut (&mut cdata as *mut _)) == noop {
+            let func = H5Tfind(self.id(), dst.id(), &mut (&mut cdata as *mut _));
+            if func == noop {
                 Some(Conversion::NoOp)
             } else {
                 match H5Tcompiler_conv(self.id(), dst.id()) {
@@ -211,6 +231,136 @@ impl Datatype {
         }
     }
 
+    /// Like `ensure_convertible()`, except that for compound datatypes all members of `dst`
+    /// are also required to be present in the source (matching them by name, so `dst` may
+    /// only contain a subset of the members), and errors name the offending member.
+    pub(crate) fn ensure_convertible_by_name(
+        &self, dst: &Self, required: Conversion,
+    ) -> Result<()> {
+        let result = self.ensure_convertible(dst, required);
+        if !self.is_compound() || !dst.is_compound() || self == dst {
+            return result;
+        }
+        let src_members = self.compound_members()?;
+        for (name, dst_member) in dst.compound_members()? {
+            let src_member = match src_members.iter().find(|(src_name, _)| *src_name == name) {
+                Some((_, src_member)) => src_member,
+                None => fail!("Field {:?} not found in the source compound datatype", name),
+            };
+            // members are walked for the missing fields of nested compounds, and to report
+            // which member is to blame if the compound as a whole is not convertible
+            if result.is_err() || src_member.is_compound() {
+                src_member
+                    .ensure_convertible_by_name(&dst_member, required)
+                    .map_err(|err| format!("Field {:?}: {}", name, err))?;
+            }
+        }
+        result
+    }
+
+    /// Ensures that this is a named datatype usable as the file type for `desc`.
+    pub(crate) fn ensure_committed_for(&self, desc: &TypeDescriptor) -> Result<()> {
+        ensure!(self.is_committed(), "Datatype is not committed");
+        Self::from_descriptor(desc)?.ensure_convertible(self, Conversion::Soft)
+    }
+
+    pub(crate) fn is_compound(&self) -> bool {
+        h5lock!(H5Tget_class(self.id())) == H5T_class_t::H5T_COMPOUND
+    }
+
+    /// Returns names and datatypes of the members of a compound datatype.
+    pub(crate) fn compound_members(&self) -> Result<Vec<(String, Self)>> {
+        h5lock!({
+            let id = self.id();
+            let mut members = Vec::new();
+            for idx in 0..h5try!(H5Tget_nmembers(id)) as _ {
+                let name = H5Tget_member_name(id, idx);
+                let ty = Self::from_id(h5try!(H5Tget_member_type(id, idx)));
+                members.push((string_from_cstr(name), ty?));
+                h5_free_memory(name.cast());
+            }
+            Ok(members)
+        })
+    }
+
+    /// Creates a compound datatype containing only the given member at offset zero.
+    pub(crate) fn single_member_compound(name: &str, member: &Self) -> Result<Self> {
+        let name = to_cstring(name)?;
+        h5lock!({
+            let compound =
+                Self::from_id(h5try!(H5Tcreate(H5T_class_t::H5T_COMPOUND, member.size())))?;
+            h5try!(H5Tinsert(compound.id(), name.as_ptr(), 0, member.id()));
+            Ok(compound)
+        })
+    }
+
+    /// Returns the dimensions of an array datatype.
+    fn array_dims(&self) -> Result<Vec<hsize_t>> {
+        h5lock!({
+            let ndims = h5try!(H5Tget_array_ndims(self.id()));
+            ensure!(ndims > 0, "Invalid rank of array datatype");
+            let mut dims: Vec<hsize_t> = vec![0; ndims as _];
+            h5try!(H5Tget_array_dims2(self.id(), dims.as_mut_ptr()));
+            Ok(dims)
+        })
+    }
+
+    /// Returns a copy of this (memory) datatype where nested arrays are merged into a single
+    /// multi-dimensional array wherever `other` has a multi-dimensional array of that shape.
+    ///
+    /// Nested Rust arrays like `[[T; 3]; 3]` are created as arrays of arrays, while HDF5 can
+    /// only convert between arrays of the same rank (e.g. to numpy subarrays of shape (3, 3)).
+    pub(crate) fn with_array_layout_of(&self, other: &Self) -> Result<Self> {
+        h5lock!({
+            let id = self.id();
+            match (H5Tget_class(id), H5Tget_class(other.id())) {
+                (H5T_class_t::H5T_ARRAY, H5T_class_t::H5T_ARRAY) => {
+                    let other_dims = other.array_dims()?;
+                    let mut dims = self.array_dims()?;
+                    let mut base = Self::from_id(h5try!(H5Tget_super(id)))?;
+                    while dims.len() < other_dims.len() && base.is_array() {
+                        dims.extend(base.array_dims()?);
+                        base = Self::from_id(h5try!(H5Tget_super(base.id())))?;
+                    }
+                    if dims != other_dims {
+                        dims = self.array_dims()?;
+                        base = Self::from_id(h5try!(H5Tget_super(id)))?;
+                    }
+                    let other_base = Self::from_id(h5try!(H5Tget_super(other.id())))?;
+                    let base = base.with_array_layout_of(&other_base)?;
+                    let ndims = dims.len() as _;
+                    Self::from_id(h5try!(H5Tarray_create2(base.id(), ndims, dims.as_ptr())))
+                }
+                (H5T_class_t::H5T_COMPOUND, H5T_class_t::H5T_COMPOUND) => {
+                    let compound =
+                        Self::from_id(h5try!(H5Tcreate(H5T_class_t::H5T_COMPOUND, self.size())))?;
+                    let other_members = other.compound_members()?;
+                    for (idx, (name, member)) in self.compound_members()?.into_iter().enumerate() {
+                        let offset = H5Tget_member_offset(id, idx as _);
+                        let member = match other_members.iter().find(|(n, _)| *n == name) {
+                            Some((_, other_member)) => member.with_array_layout_of(other_member)?,
+                            None => member,
+                        };
+                        let name = to_cstring(name)?;
+                        h5try!(H5Tinsert(compound.id(), name.as_ptr(), offset, member.id()));
+                    }
+                    Ok(compound)
+                }
+                (H5T_class_t::H5T_VLEN, H5T_class_t::H5T_VLEN) => {
+                    let base = Self::from_id(h5try!(H5Tget_super(id)))?;
+                    let other_base = Self::from_id(h5try!(H5Tget_super(other.id())))?;
+                    let base = base.with_array_layout_of(&other_base)?;
+                    Self::from_id(h5try!(H5Tvlen_create(base.id())))
+                }
+                _ => Ok(self.clone()),
+            }
+        })
+    }
+
+    fn is_array(&self) -> bool {
+        h5lock!(H5Tget_class(self.id())) == H5T_class_t::H5T_ARRAY
+    }
+
     pub fn to_descriptor(&self) -> Result<TypeDescriptor> {
         use hdf5_types::TypeDescriptor as TD;
 
@@ -224,11 +374,32 @@ impl Datatype {
                         H5T_sign_t::H5T_SGN_2 => true,
                         _ => return Err("Invalid sign of integer datatype".into()),
                     };
-                    let size = IntSize::from_int(size).ok_or("Invalid size of integer datatype")?;
+                    // odd sizes (e.g. 3-byte integers) are described by the nearest native
+                    // integer type that can hold all significant bits; reading converts to it
+                    let size = match IntSize::from_int(size) {
+                        Some(size) => size,
+                        None => {
+                            // the library reports errors as a precision of 0
+                            let precision = H5Tget_precision(id);
+                            IntSize::from_precision(precision)
+                                .filter(|_| precision != 0)
+                                .ok_or("Invalid precision of integer datatype")?
+                        }
+                    };
                     Ok(if signed { TD::Integer(size) } else { TD::Unsigned(size) })
                 }
                 H5T_class_t::H5T_FLOAT => {
                     let size = FloatSize::from_int(size).ok_or("Invalid size of float datatype")?;
+                    if size == FloatSize::U2 {
+                        // other 16-bit formats (e.g. bfloat16) can't be described as `f16`
+                        let mut fields: [size_t; 5] = [0; 5];
+                        let [spos, epos, esize, mpos, msize] = &mut fields;
+                        h5try!(H5Tget_fields(id, spos, epos, esize, mpos, msize));
+                        ensure!(
+                            fields == [15, 10, 5, 0, 10] && H5Tget_ebias(id) == 15,
+                            "Unsupported 16-bit float datatype (not IEEE half precision)"
+                        );
+                    }
                     Ok(TD::Float(size))
                 }
                 H5T_class_t::H5T_ENUM => {
@@ -258,37 +429,52 @@ impl Datatype {
                 }
                 H5T_class_t::H5T_COMPOUND => {
                     let mut fields: Vec<CompoundField> = Vec::new();
+                    let mut resized = false;
                     for idx in 0..h5try!(H5Tget_nmembers(id)) as _ {
                         let name = H5Tget_member_name(id, idx);
                         let offset = H5Tget_member_offset(id, idx);
                         let ty = Self::from_id(h5try!(H5Tget_member_type(id, idx)))?;
+                        let desc = ty.to_descriptor()?;
+                        resized |= desc.size() != ty.size();
                         fields.push(CompoundField {
                             name: string_from_cstr(name),
-                            ty: ty.to_descriptor()?,
+                            ty: desc,
                             offset: offset as _,
                             index: idx as _,
                         });
                         h5_free_memory(name.cast());
                     }
-                    Ok(TD::Compound(CompoundType { fields, size }))
+                    let compound = CompoundType { fields, size };
+                    // widened members (e.g. odd-size integers) don't fit into the file layout
+                    Ok(TD::Compound(if resized { compound.to_c_repr() } else { compound }))
                 }
                 H5T_class_t::H5T_ARRAY => {
+                    // multi-dimensional arrays are represented as nested fixed-size arrays,
+                    // e.g. an array of shape (2, 3) maps to `[[T; 3]; 2]`
                     let base_dt = Self::from_id(H5Tget_super(id))?;
-                    let ndims = h5try!(H5Tget_array_ndims(id));
-                    if ndims == 1 {
-                        let mut len: hsize_t = 0;
-                        h5try!(H5Tget_array_dims2(id, &mut len as *mut _));
-                        Ok(TD::FixedArray(Box::new(base_dt.to_descriptor()?), len as _))
-                    } else {
-                        Err("Multi-dimensional array datatypes are not supported".into())
+                    let dims = self.array_dims()?;
+                    let mut desc = base_dt.to_descriptor()?;
+                    for &len in dims.iter().rev() {
+                        desc = TD::FixedArray(Box::new(desc), len as _);
                     }
+                    Ok(desc)
                 }
                 H5T_class_t::H5T_STRING => {
                     let is_variable = h5try!(H5Tis_variable_str(id)) == 1;
+                    let padding = || -> Result<StringPadding> {
+                        match H5Tget_strpad(id) {
+                            H5T_str_t::H5T_STR_NULLTERM => Ok(StringPadding::NullTerm),
+                            H5T_str_t::H5T_STR_NULLPAD => Ok(StringPadding::NullPad),
+                            H5T_str_t::H5T_STR_SPACEPAD => Ok(StringPadding::SpacePad),
+                            _ => Err("Invalid padding for string datatype".into()),
+                        }
+                    };
                     let encoding = h5lock!(H5Tget_cset(id));
                     match (is_variable, encoding) {
-                        (false, H5T_cset_t::H5T_CSET_ASCII) => Ok(TD::FixedAscii(size)),
-                        (false, H5T_cset_t::H5T_CSET_UTF8) => Ok(TD::FixedUnicode(size)),
+                        (false, H5T_cset_t::H5T_CSET_ASCII) => Ok(TD::FixedAscii(size, padding()?)),
+                        (false, H5T_cset_t::H5T_CSET_UTF8) => {
+                            Ok(TD::FixedUnicode(size, padding()?))
+                        }
                         (true, H5T_cset_t::H5T_CSET_ASCII) => Ok(TD::VarLenAscii),
                         (true, H5T_cset_t::H5T_CSET_UTF8) => Ok(TD::VarLenUnicode),
                         _ => Err("Invalid encoding for string datatype".into()),
@@ -298,6 +484,30 @@ impl Datatype {
                     let base_dt = Self::from_id(H5Tget_super(id))?;
                     Ok(TD::VarLenArray(Box::new(base_dt.to_descriptor()?)))
                 }
+                H5T_class_t::H5T_REFERENCE => {
+                    if h5try!(H5Tequal(id, *H5T_STD_REF_OBJ)) > 0 {
+                        return Ok(TD::Reference(Reference::Object));
+                    } else if h5try!(H5Tequal(id, *H5T_STD_REF_DSETREG)) > 0 {
+                        return Ok(TD::Reference(Reference::Region));
+                    }
+                    #[cfg(feature = "1.12.0")]
+                    if h5try!(H5Tequal(id, *H5T_STD_REF)) > 0 {
+                        return Ok(TD::Reference(Reference::Std));
+                    }
+                    Err("Unsupported reference datatype".into())
+                }
+                H5T_class_t::H5T_OPAQUE => {
+                    let tag = H5Tget_tag(id);
+                    ensure!(!tag.is_null(), "Invalid tag of opaque datatype");
+                    let tag_str = string_from_cstr(tag);
+                    h5_free_memory(tag.cast());
+                    Ok(TD::Opaque { size, tag: tag_str })
+                }
+                H5T_class_t::H5T_BITFIELD => {
+                    let size =
+                        IntSize::from_int(size).ok_or("Invalid size of bitfield datatype")?;
+                    Ok(TD::Bitfield(size))
+                }
                 _ => Err("Unsupported datatype class".into()),
             }
         })
@@ -308,14 +518,30 @@ impl Datatype {
     }
 
     pub fn from_descriptor(desc: &TypeDescriptor) -> Result<Self> {
+        Self::from_descriptor_with_order(desc, ByteOrder::native())
+    }
+
+    /// Creates a datatype from a type descriptor, using the given byte order for all
+    /// integer, float and bitfield types (including those nested in compounds and arrays).
+    ///
+    /// Only `ByteOrder::LittleEndian` and `ByteOrder::BigEndian` are supported.
+    pub fn from_descriptor_with_order(desc: &TypeDescriptor, order: ByteOrder) -> Result<Self> {
         use hdf5_types::TypeDescriptor as TD;
 
-        unsafe fn string_type(size: Option<usize>, encoding: H5T_cset_t) -> Result<hid_t> {
+        ensure!(
+            matches!(order, ByteOrder::LittleEndian | ByteOrder::BigEndian),
+            "Unsupported byte order: {:?}",
+            order
+        );
+
+        unsafe fn string_type(
+            size: Option<usize>, encoding: H5T_cset_t, padding: StringPadding,
+        ) -> Result<hid_t> {
             let string_id = h5try!(H5Tcopy(*H5T_C_S1));
-            let padding = if size.is_none() {
-                H5T_str_t::H5T_STR_NULLTERM
-            } else {
-                H5T_str_t::H5T_STR_NULLPAD
+            let padding = match (size, padding) {
+                (None, _) | (_, StringPadding::NullTerm) => H5T_str_t::H5T_STR_NULLTERM,
+                (_, StringPadding::NullPad) => H5T_str_t::H5T_STR_NULLPAD,
+                (_, StringPadding::SpacePad) => H5T_str_t::H5T_STR_SPACEPAD,
             };
             let size = size.unwrap_or(H5T_VARIABLE);
             h5try!(H5Tset_cset(string_id, encoding));
@@ -327,20 +553,29 @@ impl Datatype {
         let datatype_id: Result<_> = h5lock!({
             match *desc {
                 TD::Integer(size) => Ok(match size {
-                    IntSize::U1 => be_le!(H5T_STD_I8BE, H5T_STD_I8LE),
-                    IntSize::U2 => be_le!(H5T_STD_I16BE, H5T_STD_I16LE),
-                    IntSize::U4 => be_le!(H5T_STD_I32BE, H5T_STD_I32LE),
-                    IntSize::U8 => be_le!(H5T_STD_I64BE, H5T_STD_I64LE),
+                    IntSize::U1 => be_le!(order, H5T_STD_I8BE, H5T_STD_I8LE),
+                    IntSize::U2 => be_le!(order, H5T_STD_I16BE, H5T_STD_I16LE),
+                    IntSize::U4 => be_le!(order, H5T_STD_I32BE, H5T_STD_I32LE),
+                    IntSize::U8 => be_le!(order, H5T_STD_I64BE, H5T_STD_I64LE),
                 }),
                 TD::Unsigned(size) => Ok(match size {
-                    IntSize::U1 => be_le!(H5T_STD_U8BE, H5T_STD_U8LE),
-                    IntSize::U2 => be_le!(H5T_STD_U16BE, H5T_STD_U16LE),
-                    IntSize::U4 => be_le!(H5T_STD_U32BE, H5T_STD_U32LE),
-                    IntSize::U8 => be_le!(H5T_STD_U64BE, H5T_STD_U64LE),
+                    IntSize::U1 => be_le!(order, H5T_STD_U8BE, H5T_STD_U8LE),
+                    IntSize::U2 => be_le!(order, H5T_STD_U16BE, H5T_STD_U16LE),
+                    IntSize::U4 => be_le!(order, H5T_STD_U32BE, H5T_STD_U32LE),
+                    IntSize::U8 => be_le!(order, H5T_STD_U64BE, H5T_STD_U64LE),
                 }),
                 TD::Float(size) => Ok(match size {
-                    FloatSize::U4 => be_le!(H5T_IEEE_F32BE, H5T_IEEE_F32LE),
-                    FloatSize::U8 => be_le!(H5T_IEEE_I16BE, H5T_IEEE_F64LE),
+                    FloatSize::U2 => {
+                        // there's no predefined half-precision type, so derive one from
+                        // float32 (1 sign bit, 5 exponent bits, 10 mantissa bits)
+                        let f16_id = be_le!(order, H5T_IEEE_F32BE, H5T_IEEE_F32LE);
+                        h5try!(H5Tset_fields(f16_id, 15, 10, 5, 0, 10));
+                        h5try!(H5Tset_size(f16_id, 2));
+                        h5try!(H5Tset_ebias(f16_id, 15));
+                        f16_id
+                    }
+                    FloatSize::U4 => be_le!(order, H5T_IEEE_F32BE, H5T_IEEE_F32LE),
+                    FloatSize::U8 => be_le!(order, H5T_IEEE_F64BE, H5T_IEEE_F64LE),
                 }),
                 TD::Boolean => {
                     let bool_id = h5try!(H5Tenum_create(*H5T_NATIVE_INT8));
@@ -357,7 +592,7 @@ impl Datatype {
                     Ok(bool_id)
                 }
                 TD::Enum(ref enum_type) => {
-                    let base = Self::from_descriptor(&enum_type.base_type())?;
+                    let base = Self::from_descriptor_with_order(&enum_type.base_type(), order)?;
                     let enum_id = h5try!(H5Tenum_create(base.id()));
                     for member in &enum_type.members {
                         let name = to_cstring(member.name.as_ref())?;
@@ -373,7 +608,7 @@ impl Datatype {
                     let compound_id = h5try!(H5Tcreate(H5T_class_t::H5T_COMPOUND, 1));
                     for field in &compound_type.fields {
                         let name = to_cstring(field.name.as_ref())?;
-                        let field_dt = Self::from_descriptor(&field.ty)?;
+                        let field_dt = Self::from_descriptor_with_order(&field.ty, order)?;
                         h5try!(H5Tset_size(compound_id, field.offset + field.ty.size()));
                         h5try!(H5Tinsert(compound_id, name.as_ptr(), field.offset, field_dt.id()));
                     }
@@ -381,18 +616,44 @@ impl Datatype {
                     Ok(compound_id)
                 }
                 TD::FixedArray(ref ty, len) => {
-                    let elem_dt = Self::from_descriptor(ty)?;
+                    let elem_dt = Self::from_descriptor_with_order(ty, order)?;
                     let dims = len as hsize_t;
                     Ok(h5try!(H5Tarray_create2(elem_dt.id(), 1, &dims as *const _)))
                 }
-                TD::FixedAscii(size) => string_type(Some(size), H5T_cset_t::H5T_CSET_ASCII),
-                TD::FixedUnicode(size) => string_type(Some(size), H5T_cset_t::H5T_CSET_UTF8),
+                TD::FixedAscii(size, padding) => {
+                    string_type(Some(size), H5T_cset_t::H5T_CSET_ASCII, padding)
+                }
+                TD::FixedUnicode(size, padding) => {
+                    string_type(Some(size), H5T_cset_t::H5T_CSET_UTF8, padding)
+                }
                 TD::VarLenArray(ref ty) => {
-                    let elem_dt = Self::from_descriptor(ty)?;
+                    let elem_dt = Self::from_descriptor_with_order(ty, order)?;
                     Ok(h5try!(H5Tvlen_create(elem_dt.id())))
                 }
-                TD::VarLenAscii => string_type(None, H5T_cset_t::H5T_CSET_ASCII),
-                TD::VarLenUnicode => string_type(None, H5T_cset_t::H5T_CSET_UTF8),
+                TD::VarLenAscii => {
+                    string_type(None, H5T_cset_t::H5T_CSET_ASCII, StringPadding::NullTerm)
+                }
+                TD::VarLenUnicode => {
+                    string_type(None, H5T_cset_t::H5T_CSET_UTF8, StringPadding::NullTerm)
+                }
+                TD::Reference(Reference::Object) => Ok(h5try!(H5Tcopy(*H5T_STD_REF_OBJ))),
+                TD::Reference(Reference::Region) => Ok(h5try!(H5Tcopy(*H5T_STD_REF_DSETREG))),
+                #[cfg(feature = "1.12.0")]
+                TD::Reference(Reference::Std) => Ok(h5try!(H5Tcopy(*H5T_STD_REF))),
+                TD::Opaque { size, ref tag } => {
+                    let opaque_id = h5try!(H5Tcreate(H5T_class_t::H5T_OPAQUE, size));
+                    if !tag.is_empty() {
+                        let tag = to_cstring(tag.as_ref())?;
+                        h5try!(H5Tset_tag(opaque_id, tag.as_ptr()));
+                    }
+                    Ok(opaque_id)
+                }
+                TD::Bitfield(size) => Ok(match size {
+                    IntSize::U1 => be_le!(order, H5T_STD_B8BE, H5T_STD_B8LE),
+                    IntSize::U2 => be_le!(order, H5T_STD_B16BE, H5T_STD_B16LE),
+                    IntSize::U4 => be_le!(order, H5T_STD_B32BE, H5T_STD_B32LE),
+                    IntSize::U8 => be_le!(order, H5T_STD_B64BE, H5T_STD_B64LE),
+                }),
             }
         });
 
@@ -11,7 +11,7 @@ use hdf5_sys::{
         H5Ldelete, H5Lexists, H5Literate, H5Lmove, H5L_SAME_LOC,
     },
     h5p::{H5Pcreate, H5Pset_create_intermediate_group},
-    h5t::H5T_cset_t,
+    h5t::{H5T_cset_t, H5Tcommit2, H5Tcopy, H5Topen2},
 };
 
 use crate::globals::H5P_LINK_CREATE;
@@ -214,6 +214,33 @@ impl Group {
         let name = to_cstring(name)?;
         Dataset::from_id(h5try!(H5Dopen2(self.id(), name.as_ptr(), H5P_DEFAULT)))
     }
+
+    /// Commits a copy of the datatype to the file under the given name.
+    ///
+    /// The returned named datatype can be shared by datasets and attributes by passing it
+    /// to `committed_type()` on their builders.
+    pub fn commit_datatype(&self, name: &str, dtype: &Datatype) -> Result<Datatype> {
+        h5lock!({
+            let lcpl = make_lcpl()?;
+            let name = to_cstring(name)?;
+            let named = Datatype::from_id(h5try!(H5Tcopy(dtype.id())))?;
+            h5try!(H5Tcommit2(
+                self.id(),
+                name.as_ptr(),
+                named.id(),
+                lcpl.id(),
+                H5P_DEFAULT,
+                H5P_DEFAULT
+            ));
+            Ok(named)
+        })
+    }
+
+    /// Opens an existing named datatype in the file or group.
+    pub fn datatype(&self, name: &str) -> Result<Datatype> {
+        let name = to_cstring(name)?;
+        Datatype::from_id(h5try!(H5Topen2(self.id(), name.as_ptr(), H5P_DEFAULT)))
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -403,6 +430,25 @@ impl Group {
     }
 }
 
+/// A type that can be stored as a group of datasets, attributes and subgroups.
+///
+/// This is typically derived via `#[derive(H5Group)]`: fields holding `ndarray` arrays are
+/// stored as datasets (the `chunk` and `deflate` options are passed to the dataset builder),
+/// fields marked with `#[hdf5(group)]` as subgroups, and all other fields as scalar
+/// attributes. Fields can be renamed via `#[hdf5(rename = "...")]`, and `Option<T>` fields
+/// are only stored if they are set.
+///
+/// Arrays are recognized by the last segment of the field's type path (`Array`, `ArrayD` or
+/// `Array0` to `Array6`), so fields of other array types, like `ArrayBase<...>` or type
+/// aliases, have to be marked with `#[hdf5(dataset)]` to be stored as datasets.
+pub trait H5Group: Sized {
+    /// Creates a new group with the given name and stores all fields in it.
+    fn save(&self, parent: &Group, name: &str) -> Result<()>;
+
+    /// Loads the fields from the group with the given name.
+    fn load(parent: &Group, name: &str) -> Result<Self>;
+}
+
 #[cfg(test)]
 pub mod tests {
     use crate::internal_prelude::*;
@@ -675,4 +721,39 @@ pub mod tests {
             }
         })
     }
+
+    #[test]
+    pub fn test_commit_datatype() {
+        with_tmp_file(|file| {
+            let dtype = Datatype::from_type::<u32>().unwrap();
+            let event = file.commit_datatype("types/Event", &dtype).unwrap();
+            assert!(event.is_committed());
+            assert!(!dtype.is_committed());
+            assert_eq!(file.group("types").unwrap().named_datatypes().unwrap().len(), 1);
+            assert_eq!(file.datatype("types/Event").unwrap(), event);
+            assert!(file.commit_datatype("types/Event", &dtype).is_err());
+
+            let ds = file.new_dataset::<u32>().committed_type(&event).shape(3).create("a").unwrap();
+            assert!(ds.dtype().unwrap().is_committed());
+            let ds = file
+                .new_dataset_builder()
+                .committed_type(&event)
+                .with_data(&[1_u16, 2])
+                .create("b")
+                .unwrap();
+            assert!(ds.dtype().unwrap().is_committed());
+            assert_eq!(ds.read_raw::<u32>().unwrap(), vec![1, 2]);
+            let attr = ds.new_attr::<u32>().committed_type(&event).create("c").unwrap();
+            assert!(attr.dtype().unwrap().is_committed());
+
+            assert_err!(
+                file.new_dataset::<u32>().committed_type(&dtype).create("d"),
+                "Datatype is not committed"
+            );
+            assert_err!(
+                file.new_dataset::<bool>().committed_type(&event).create("e"),
+                "no conversion paths found"
+            );
+        })
+    }
 }
@@ -18,7 +18,7 @@ use hdf5_sys::h5o::{H5O_INFO_BASIC, H5O_INFO_NUM_ATTRS, H5O_INFO_TIME};
 #[cfg(not(feature = "1.12.0"))]
 use hdf5_sys::{h5::haddr_t, h5o::H5O_info1_t, h5o::H5Oopen_by_addr};
 use hdf5_sys::{
-    h5a::H5Aopen,
+    h5a::{H5Adelete, H5Aexists, H5Aopen},
     h5f::H5Fget_name,
     h5i::{H5Iget_file_id, H5Iget_name},
     h5o::{H5O_type_t, H5Oget_comment},
@@ -125,6 +125,21 @@ impl Location {
         Attribute::attr_names(self)
     }
 
+    /// Checks if an attribute with the given name exists.
+    pub fn attr_exists(&self, name: &str) -> bool {
+        (|| -> Result<bool> {
+            let name = to_cstring(name)?;
+            Ok(h5call!(H5Aexists(self.id(), name.as_ptr()))? > 0)
+        })()
+        .unwrap_or(false)
+    }
+
+    /// Removes the attribute with the given name.
+    pub fn delete_attr(&self, name: &str) -> Result<()> {
+        let name = to_cstring(name)?;
+        h5call!(H5Adelete(self.id(), name.as_ptr())).and(Ok(()))
+    }
+
     pub fn loc_info(&self) -> Result<LocationInfo> {
         H5O_get_info(self.id(), true)
     }
@@ -335,6 +350,18 @@ pub mod tests {
         })
     }
 
+    #[test]
+    pub fn test_attr_exists_delete() {
+        with_tmp_file(|file| {
+            assert!(!file.attr_exists("foo"));
+            file.new_attr::<u32>().create("foo").unwrap();
+            assert!(file.attr_exists("foo"));
+            file.delete_attr("foo").unwrap();
+            assert!(!file.attr_exists("foo"));
+            assert!(file.delete_attr("foo").is_err());
+        })
+    }
+
     #[test]
     pub fn test_location_info() {
         let new_file = |path| {
@@ -0,0 +1,489 @@
+use std::convert::{TryFrom, TryInto};
+use std::fmt::{self, Debug};
+
+#[cfg(not(feature = "1.10.0"))]
+use hdf5_sys::h5r::H5Rdereference1;
+#[cfg(feature = "1.10.0")]
+use hdf5_sys::h5r::H5Rdereference2;
+#[cfg(feature = "1.12.0")]
+use hdf5_sys::h5r::{
+    H5R_ref_t, H5Rcopy, H5Rcreate_attr, H5Rcreate_object, H5Rcreate_region, H5Rdestroy, H5Requal,
+    H5Rget_attr_name, H5Rget_obj_name, H5Rget_type, H5Ropen_attr, H5Ropen_object, H5Ropen_region,
+};
+use hdf5_sys::h5r::{H5R_type_t, H5Rcreate, H5Rget_region};
+use hdf5_types::{ObjectReference, RegionReference};
+#[cfg(feature = "1.12.0")]
+use hdf5_types::{Reference, TypeDescriptor};
+
+use crate::internal_prelude::*;
+
+#[cfg(not(feature = "1.12.0"))]
+const H5R_OBJECT: H5R_type_t = H5R_type_t::H5R_OBJECT;
+#[cfg(feature = "1.12.0")]
+const H5R_OBJECT: H5R_type_t = H5R_type_t::H5R_OBJECT1;
+
+#[cfg(not(feature = "1.12.0"))]
+const H5R_DATASET_REGION: H5R_type_t = H5R_type_t::H5R_DATASET_REGION;
+#[cfg(feature = "1.12.0")]
+const H5R_DATASET_REGION: H5R_type_t = H5R_type_t::H5R_DATASET_REGION1;
+
+#[allow(non_snake_case)]
+fn H5R_dereference(loc_id: hid_t, ref_type: H5R_type_t, reference: *const c_void) -> Result<hid_t> {
+    #[cfg(not(feature = "1.10.0"))]
+    {
+        h5call!(H5Rdereference1(loc_id, ref_type, reference))
+    }
+    #[cfg(feature = "1.10.0")]
+    {
+        h5call!(H5Rdereference2(loc_id, H5P_DEFAULT, ref_type, reference))
+    }
+}
+
+/// Object references
+impl Location {
+    /// Creates a reference to the object at `name` (relative to this location).
+    ///
+    /// The object must be a group, a dataset or a named datatype. The resulting reference
+    /// can be stored in datasets and attributes of type `ObjectReference`.
+    pub fn reference(&self, name: &str) -> Result<ObjectReference> {
+        let name = to_cstring(name)?;
+        let mut reference = ObjectReference::null();
+        h5call!(H5Rcreate(
+            reference.as_mut_ptr(),
+            self.id(),
+            name.as_ptr(),
+            H5R_OBJECT,
+            H5I_INVALID_HID
+        ))?;
+        Ok(reference)
+    }
+
+    /// Opens the object pointed to by an object reference.
+    ///
+    /// The reference must have been created within the same file as this location; the
+    /// returned location can be downcast via `as_group()`, `as_dataset()`, etc.
+    pub fn dereference(&self, reference: &ObjectReference) -> Result<Self> {
+        ensure!(!reference.is_null(), "Cannot dereference a null object reference");
+        Self::from_id(H5R_dereference(self.id(), H5R_OBJECT, reference.as_ptr())?)
+    }
+
+    /// Opens the dataset pointed to by a region reference and returns it along with
+    /// the referenced selection.
+    ///
+    /// The reference must have been created within the same file as this location.
+    pub fn dereference_region(&self, reference: &RegionReference) -> Result<(Dataset, Selection)> {
+        ensure!(!reference.is_null(), "Cannot dereference a null region reference");
+        h5lock!({
+            let ptr = reference.as_ptr();
+            let dataset = Dataset::from_id(H5R_dereference(self.id(), H5R_DATASET_REGION, ptr)?)?;
+            let space =
+                Dataspace::from_id(h5try!(H5Rget_region(self.id(), H5R_DATASET_REGION, ptr)))?;
+            Ok((dataset, space.get_selection()?))
+        })
+    }
+
+    /// Creates a new-style reference to the object at `name` (relative to this location).
+    #[cfg(feature = "1.12.0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "1.12.0")))]
+    pub fn std_reference(&self, name: &str) -> Result<StdReference> {
+        let name = to_cstring(name)?;
+        let mut reference = H5R_ref_t::default();
+        h5call!(H5Rcreate_object(self.id(), name.as_ptr(), H5P_DEFAULT, &mut reference))?;
+        Ok(StdReference(reference))
+    }
+
+    /// Creates a reference to the attribute `attr_name` attached to this location.
+    #[cfg(feature = "1.12.0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "1.12.0")))]
+    pub fn attr_reference(&self, attr_name: &str) -> Result<AttributeReference> {
+        let attr_name = to_cstring(attr_name)?;
+        let mut reference = H5R_ref_t::default();
+        h5call!(H5Rcreate_attr(
+            self.id(),
+            b".\0".as_ptr().cast(),
+            attr_name.as_ptr(),
+            H5P_DEFAULT,
+            &mut reference
+        ))?;
+        Ok(AttributeReference(StdReference(reference)))
+    }
+}
+
+/// Region references
+impl Dataset {
+    fn select_for_reference<S>(&self, selection: S) -> Result<Dataspace>
+    where
+        S: TryInto<Selection>,
+        Error: From<S::Error>,
+    {
+        let selection = selection.try_into()?;
+        let space = self.space()?;
+        ensure!(!space.is_scalar(), "Region references cannot be created for scalar datasets");
+        space.select(selection)
+    }
+
+    /// Creates a reference to the given selection within this dataset.
+    ///
+    /// The resulting reference can be stored in datasets and attributes of type
+    /// `RegionReference` and resolved back via `Location::dereference_region()`.
+    pub fn region_reference<S>(&self, selection: S) -> Result<RegionReference>
+    where
+        S: TryInto<Selection>,
+        Error: From<S::Error>,
+    {
+        let space = self.select_for_reference(selection)?;
+        let mut reference = RegionReference::null();
+        h5call!(H5Rcreate(
+            reference.as_mut_ptr(),
+            self.id(),
+            b".\0".as_ptr().cast(),
+            H5R_DATASET_REGION,
+            space.id()
+        ))?;
+        Ok(reference)
+    }
+
+    /// Creates a new-style reference to the given selection within this dataset.
+    #[cfg(feature = "1.12.0")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "1.12.0")))]
+    pub fn std_region_reference<S>(&self, selection: S) -> Result<StdReference>
+    where
+        S: TryInto<Selection>,
+        Error: From<S::Error>,
+    {
+        let space = self.select_for_reference(selection)?;
+        let mut reference = H5R_ref_t::default();
+        h5call!(H5Rcreate_region(
+            self.id(),
+            b".\0".as_ptr().cast(),
+            space.id(),
+            H5P_DEFAULT,
+            &mut reference
+        ))?;
+        Ok(StdReference(reference))
+    }
+}
+
+/// Kind of object a [`StdReference`] points to.
+#[cfg(feature = "1.12.0")]
+#[cfg_attr(docsrs, doc(cfg(feature = "1.12.0")))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StdReferenceType {
+    Object,
+    Region,
+    Attribute,
+}
+
+/// A new-style HDF5 reference (`H5R_ref_t`), available since HDF5 1.12.
+///
+/// Unlike classic references, these also encode the file they point into, so they can be
+/// resolved without providing a location. They hold resources inside the HDF5 library
+/// which are released when the reference is dropped.
+#[cfg(feature = "1.12.0")]
+#[cfg_attr(docsrs, doc(cfg(feature = "1.12.0")))]
+#[repr(transparent)]
+pub struct StdReference(H5R_ref_t);
+
+#[cfg(feature = "1.12.0")]
+unsafe impl H5Type for StdReference {
+    fn type_descriptor() -> TypeDescriptor {
+        TypeDescriptor::Reference(Reference::Std)
+    }
+}
+
+#[cfg(feature = "1.12.0")]
+impl StdReference {
+    /// Returns the kind of object this reference points to.
+    pub fn ref_type(&self) -> Result<StdReferenceType> {
+        match h5lock!(H5Rget_type(&self.0)) {
+            H5R_type_t::H5R_OBJECT2 => Ok(StdReferenceType::Object),
+            H5R_type_t::H5R_DATASET_REGION2 => Ok(StdReferenceType::Region),
+            H5R_type_t::H5R_ATTR => Ok(StdReferenceType::Attribute),
+            ref_type => fail!("Unsupported reference type: {:?}", ref_type),
+        }
+    }
+
+    /// Returns the name of the object pointed to by this reference (for attribute references,
+    /// the object the attribute is attached to).
+    pub fn object_name(&self) -> Result<String> {
+        h5lock!(get_h5_str(|m, s| H5Rget_obj_name(&self.0, H5P_DEFAULT, m, s)))
+    }
+
+    /// Opens the object pointed to by this reference (for region references, this is
+    /// the dataset containing the region).
+    pub fn dereference(&self) -> Result<Location> {
+        Location::from_id(h5try!(H5Ropen_object(&self.0, H5P_DEFAULT, H5P_DEFAULT)))
+    }
+
+    /// Opens the dataset pointed to by this region reference and returns it along with
+    /// the referenced selection.
+    pub fn dereference_region(&self) -> Result<(Dataset, Selection)> {
+        ensure!(
+            self.ref_type()? == StdReferenceType::Region,
+            "Cannot dereference a region: not a region reference"
+        );
+        h5lock!({
+            let dataset =
+                Dataset::from_id(h5try!(H5Ropen_object(&self.0, H5P_DEFAULT, H5P_DEFAULT)))?;
+            let space =
+                Dataspace::from_id(h5try!(H5Ropen_region(&self.0, H5P_DEFAULT, H5P_DEFAULT)))?;
+            Ok((dataset, space.get_selection()?))
+        })
+    }
+
+    /// Opens the attribute pointed to by this attribute reference.
+    pub fn dereference_attr(&self) -> Result<Attribute> {
+        ensure!(
+            self.ref_type()? == StdReferenceType::Attribute,
+            "Cannot dereference an attribute: not an attribute reference"
+        );
+        Attribute::from_id(h5try!(H5Ropen_attr(&self.0, H5P_DEFAULT, H5P_DEFAULT)))
+    }
+}
+
+#[cfg(feature = "1.12.0")]
+impl Clone for StdReference {
+    fn clone(&self) -> Self {
+        let mut reference = H5R_ref_t::default();
+        h5lock!(H5Rcopy(&self.0, &mut reference));
+        Self(reference)
+    }
+}
+
+#[cfg(feature = "1.12.0")]
+impl Drop for StdReference {
+    fn drop(&mut self) {
+        h5lock!(H5Rdestroy(&mut self.0));
+    }
+}
+
+#[cfg(feature = "1.12.0")]
+impl PartialEq for StdReference {
+    fn eq(&self, other: &Self) -> bool {
+        h5lock!(H5Requal(&self.0, &other.0)) > 0
+    }
+}
+
+#[cfg(feature = "1.12.0")]
+impl Debug for StdReference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.ref_type() {
+            Ok(ref_type) => write!(f, "StdReference({:?})", ref_type),
+            Err(_) => f.write_str("StdReference(invalid)"),
+        }
+    }
+}
+
+/// A reference to an attribute, available since HDF5 1.12.
+///
+/// This is a [`StdReference`] which is known to point to an attribute. Since stored
+/// references can point to any kind of object, it is not an [`H5Type`] itself: attribute
+/// references are stored and read as `StdReference` and converted via `From`/`TryFrom`
+/// (the latter checks that the reference does point to an attribute).
+#[cfg(feature = "1.12.0")]
+#[cfg_attr(docsrs, doc(cfg(feature = "1.12.0")))]
+#[derive(Clone, PartialEq)]
+pub struct AttributeReference(StdReference);
+
+#[cfg(feature = "1.12.0")]
+impl AttributeReference {
+    /// Opens the attribute pointed to by this reference.
+    pub fn dereference(&self) -> Result<Attribute> {
+        self.0.dereference_attr()
+    }
+
+    /// Returns the name of the referenced attribute.
+    pub fn name(&self) -> Result<String> {
+        h5lock!(get_h5_str(|m, s| H5Rget_attr_name(&self.0 .0, m, s)))
+    }
+
+    /// Returns the name of the object the referenced attribute is attached to.
+    pub fn object_name(&self) -> Result<String> {
+        self.0.object_name()
+    }
+
+    /// Returns the underlying untyped reference.
+    pub fn as_std(&self) -> &StdReference {
+        &self.0
+    }
+}
+
+#[cfg(feature = "1.12.0")]
+impl TryFrom<StdReference> for AttributeReference {
+    type Error = Error;
+
+    fn try_from(reference: StdReference) -> Result<Self> {
+        ensure!(
+            reference.ref_type()? == StdReferenceType::Attribute,
+            "Expected an attribute reference"
+        );
+        Ok(Self(reference))
+    }
+}
+
+#[cfg(feature = "1.12.0")]
+impl From<AttributeReference> for StdReference {
+    fn from(reference: AttributeReference) -> Self {
+        reference.0
+    }
+}
+
+#[cfg(feature = "1.12.0")]
+impl Debug for AttributeReference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.object_name(), self.name()) {
+            (Ok(object), Ok(name)) => {
+                f.debug_tuple("AttributeReference").field(&object).field(&name).finish()
+            }
+            _ => f.write_str("AttributeReference(invalid)"),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use ndarray::s;
+
+    use crate::internal_prelude::*;
+
+    #[test]
+    pub fn test_object_references() {
+        with_tmp_file(|file| {
+            let group = file.create_group("a/b").unwrap();
+            let ds = group.new_dataset::<i32>().shape(3).create("ds").unwrap();
+            ds.write(&[1, 2, 3]).unwrap();
+
+            let group_ref = file.reference("a/b").unwrap();
+            let ds_ref = group.reference("ds").unwrap();
+            assert!(!group_ref.is_null());
+            assert_ne!(group_ref, ds_ref);
+            assert!(file.reference("gibberish").is_err());
+
+            let refs = file.new_dataset_builder().with_data(&[group_ref, ds_ref]).create("refs");
+            let refs = refs.unwrap().read_raw::<types::ObjectReference>().unwrap();
+            assert_eq!(refs, vec![group_ref, ds_ref]);
+
+            let group = file.dereference(&refs[0]).unwrap().as_group().unwrap();
+            assert_eq!(group.name(), "/a/b");
+            let ds = ds.dereference(&refs[1]).unwrap().as_dataset().unwrap();
+            assert_eq!(ds.name(), "/a/b/ds");
+            assert_eq!(ds.read_raw::<i32>().unwrap(), vec![1, 2, 3]);
+
+            let attr = file.new_attr::<types::ObjectReference>().create("ref").unwrap();
+            attr.write_scalar(&ds_ref).unwrap();
+            let ds_ref = attr.read_scalar::<types::ObjectReference>().unwrap();
+            assert_eq!(file.dereference(&ds_ref).unwrap().name(), "/a/b/ds");
+
+            let null = types::ObjectReference::null();
+            assert_err!(file.dereference(&null), "null object reference");
+        })
+    }
+
+    #[test]
+    pub fn test_region_references() {
+        with_tmp_file(|file| {
+            let arr = ndarray::Array2::from_shape_fn((10, 4), |(i, j)| (i * 10 + j) as i32);
+            let ds = file.new_dataset_builder().with_data(&arr).create("raw/signal").unwrap();
+
+            let rows = ds.region_reference(2..5).unwrap();
+            let block = ds.region_reference(s![1..3, 2..]).unwrap();
+            assert!(!rows.is_null());
+            assert_ne!(rows, block);
+
+            let refs = file.new_dataset_builder().with_data(&[rows, block]).create("refs");
+            let refs = refs.unwrap().read_raw::<types::RegionReference>().unwrap();
+            assert_eq!(refs, vec![rows, block]);
+
+            let (target, selection) = file.dereference_region(&refs[0]).unwrap();
+            assert_eq!(target.name(), "/raw/signal");
+            let data: ndarray::Array2<i32> = target.read_slice(selection).unwrap();
+            assert_eq!(data, arr.slice(s![2..5, ..]));
+
+            let (target, selection) = file.dereference_region(&refs[1]).unwrap();
+            let data: ndarray::Array2<i32> = target.read_slice(selection).unwrap();
+            assert_eq!(data, arr.slice(s![1..3, 2..]));
+
+            let scalar = file.new_dataset::<i32>().create("scalar").unwrap();
+            assert_err!(scalar.region_reference(..), "scalar datasets");
+
+            let null = types::RegionReference::null();
+            assert_err!(file.dereference_region(&null), "null region reference");
+        })
+    }
+
+    #[test]
+    #[cfg(feature = "1.12.0")]
+    pub fn test_std_references() {
+        use crate::hl::references::{StdReference, StdReferenceType};
+
+        with_tmp_file(|file| {
+            let arr = ndarray::Array2::from_shape_fn((10, 4), |(i, j)| (i * 10 + j) as i32);
+            let ds = file.new_dataset_builder().with_data(&arr).create("raw/signal").unwrap();
+
+            let object = file.std_reference("raw/signal").unwrap();
+            let region = ds.std_region_reference(s![1..3, 2..]).unwrap();
+            assert_eq!(object.ref_type().unwrap(), StdReferenceType::Object);
+            assert_eq!(region.ref_type().unwrap(), StdReferenceType::Region);
+            assert_eq!(region.clone(), region);
+            assert_ne!(object, region);
+
+            let refs = file.new_dataset::<StdReference>().shape(2).create("refs").unwrap();
+            refs.write(&[object.clone(), region.clone()]).unwrap();
+            let refs = refs.read_raw::<StdReference>().unwrap();
+            assert_eq!(refs, vec![object, region]);
+
+            assert_eq!(refs[0].dereference().unwrap().name(), "/raw/signal");
+            assert_err!(refs[0].dereference_region(), "not a region reference");
+            let (target, selection) = refs[1].dereference_region().unwrap();
+            assert_eq!(target.name(), "/raw/signal");
+            let data: ndarray::Array2<i32> = target.read_slice(selection).unwrap();
+            assert_eq!(data, arr.slice(s![1..3, 2..]));
+        })
+    }
+
+    #[test]
+    #[cfg(feature = "1.12.0")]
+    pub fn test_attribute_references() {
+        use std::convert::TryFrom;
+
+        use crate::hl::references::{AttributeReference, StdReference, StdReferenceType};
+
+        with_tmp_file(|file| {
+            let ds = file.new_dataset::<f64>().shape(4).create("raw/signal").unwrap();
+            ds.new_attr::<f64>().create("gain").unwrap().write_scalar(&1.5).unwrap();
+            file.new_attr::<i32>().create("version").unwrap().write_scalar(&3).unwrap();
+
+            let gain = ds.attr_reference("gain").unwrap();
+            let version = file.attr_reference("version").unwrap();
+            assert_eq!(gain.as_std().ref_type().unwrap(), StdReferenceType::Attribute);
+            assert_ne!(gain, version);
+            assert!(ds.attr_reference("gibberish").is_err());
+
+            assert_eq!(gain.name().unwrap(), "gain");
+            assert_eq!(gain.object_name().unwrap(), "/raw/signal");
+            assert_eq!(format!("{:?}", version), "AttributeReference(\"/\", \"version\")");
+
+            let catalog = file.new_dataset::<StdReference>().shape(3).create("catalog").unwrap();
+            let object = file.std_reference("raw/signal").unwrap();
+            catalog.write(&[gain.clone().into(), version.clone().into(), object]).unwrap();
+            let mut refs = catalog.read_raw::<StdReference>().unwrap();
+            let object = refs.pop().unwrap();
+            let refs = refs
+                .into_iter()
+                .map(AttributeReference::try_from)
+                .collect::<Result<Vec<_>>>()
+                .unwrap();
+            assert_eq!(refs, vec![gain, version]);
+
+            let attr = refs[0].dereference().unwrap();
+            assert_eq!(attr.name(), "/raw/signal");
+            assert_eq!(attr.read_scalar::<f64>().unwrap(), 1.5);
+            assert_eq!(refs[1].dereference().unwrap().read_scalar::<i32>().unwrap(), 3);
+
+            let std = StdReference::from(refs[1].clone());
+            assert_eq!(std.dereference_attr().unwrap().read_scalar::<i32>().unwrap(), 3);
+            assert_err!(object.dereference_attr(), "not an attribute reference");
+            assert_err!(AttributeReference::try_from(object), "Expected an attribute reference");
+        })
+    }
+}
@@ -67,8 +67,11 @@ mod export {
 
     #[doc(hidden)]
     pub use crate::error::h5check;
+    #[doc(hidden)]
+    pub use crate::hl::attribute::{read_attr_field, write_attr_field};
 
-    pub use hdf5_derive::H5Type;
+    pub use crate::hl::{H5Attrs, H5Group};
+    pub use hdf5_derive::{H5Attrs, H5Group, H5Type};
     pub use hdf5_types::H5Type;
 
     pub mod types {
@@ -93,6 +96,12 @@ mod export {
         pub use crate::hl::plist::file_create::*;
     }
 
+    pub mod references {
+        #[cfg(feature = "1.12.0")]
+        pub use crate::hl::references::{AttributeReference, StdReference, StdReferenceType};
+        pub use hdf5_types::{ObjectReference, RegionReference};
+    }
+
     pub mod plist {
         pub use crate::hl::plist::dataset_access::{DatasetAccess, DatasetAccessBuilder};
         pub use crate::hl::plist::dataset_create::{DatasetCreate, DatasetCreateBuilder};
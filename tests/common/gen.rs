@@ -2,7 +2,9 @@ use std::convert::TryFrom;
 use std::fmt;
 use std::iter;
 
-use hdf5::types::{FixedAscii, FixedUnicode, VarLenArray, VarLenAscii, VarLenUnicode};
+use hdf5::types::{
+    Bitfield, FixedAscii, FixedUnicode, Opaque, VarLenArray, VarLenAscii, VarLenUnicode,
+};
 use hdf5::H5Type;
 
 use ndarray::{ArrayD, SliceInfo, SliceInfoElem};
@@ -174,6 +176,20 @@ impl<T: Gen + Copy> Gen for VarLenArray<T> {
     }
 }
 
+impl<const N: usize> Gen for Opaque<N> {
+    fn gen<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let mut bytes = [0; N];
+        rng.fill(&mut bytes[..]);
+        Opaque::new(bytes)
+    }
+}
+
+impl<T: Gen + Copy + fmt::Binary> Gen for Bitfield<T> {
+    fn gen<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Bitfield::new(Gen::gen(rng))
+    }
+}
+
 #[derive(H5Type, Clone, Copy, Debug, PartialEq)]
 #[repr(i16)]
 pub enum Enum {
@@ -230,3 +246,17 @@ impl Gen for VarLenStruct {
         VarLenStruct { va: Gen::gen(rng), vu: Gen::gen(rng), vla: Gen::gen(rng) }
     }
 }
+
+#[derive(H5Type, Clone, Copy, Debug, PartialEq)]
+#[repr(C)]
+pub struct RawStruct {
+    header: Opaque<5>,
+    flags: Bitfield<u16>,
+    value: f32,
+}
+
+impl Gen for RawStruct {
+    fn gen<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        RawStruct { header: Gen::gen(rng), flags: Gen::gen(rng), value: Gen::gen(rng) }
+    }
+}
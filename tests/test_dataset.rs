@@ -6,9 +6,12 @@ use rand::prelude::{Rng, SeedableRng, SmallRng};
 
 use hdf5_types::TypeDescriptor;
 
+#[macro_use]
 mod common;
 
-use self::common::gen::{gen_arr, gen_slice, Enum, FixedStruct, Gen, TupleStruct, VarLenStruct};
+use self::common::gen::{
+    gen_arr, gen_slice, Enum, FixedStruct, Gen, RawStruct, TupleStruct, VarLenStruct,
+};
 use self::common::util::new_in_memory_file;
 
 fn test_write_slice<T, R>(
@@ -230,6 +233,460 @@ fn test_read_write_primitive() -> hdf5::Result<()> {
     Ok(())
 }
 
+#[test]
+#[cfg(feature = "half")]
+fn test_read_write_f16() -> hdf5::Result<()> {
+    use half::f16;
+
+    let file = new_in_memory_file()?;
+    let values = [0.5_f32, -2.0, 1024.0, 65504.0, 1e-3];
+    let halves: Vec<f16> = values.iter().copied().map(f16::from_f32).collect();
+    let ds = file.new_dataset_builder().with_data(&halves).create("activations")?;
+    assert_eq!(ds.dtype()?.size(), 2);
+    assert_eq!(ds.read_raw::<f16>()?, halves);
+
+    let as_f32 = ds.read_raw::<f32>()?;
+    let expected: Vec<f32> = halves.iter().copied().map(f32::from).collect();
+    assert_eq!(as_f32, expected);
+
+    let ds = file.new_dataset::<f16>().shape(values.len()).create("from_f32")?;
+    ds.write(&values)?;
+    assert_eq!(ds.read_raw::<f16>()?, halves);
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "complex")]
+fn test_read_write_complex() -> hdf5::Result<()> {
+    use hdf5::H5Type;
+    use num_complex::{Complex32, Complex64};
+
+    // the layout h5py uses for complex numbers
+    #[derive(H5Type, Clone, Copy, Debug, PartialEq)]
+    #[repr(C)]
+    struct PyComplex {
+        r: f64,
+        i: f64,
+    }
+
+    let file = new_in_memory_file()?;
+    let values = [Complex64::new(1.0, -2.0), Complex64::new(0.5, 3.25)];
+    let ds = file.new_dataset_builder().with_data(&values).create("signal")?;
+    assert_eq!(ds.read_raw::<Complex64>()?, values);
+    assert_eq!(
+        ds.read_raw::<PyComplex>()?,
+        vec![PyComplex { r: 1.0, i: -2.0 }, PyComplex { r: 0.5, i: 3.25 }]
+    );
+    assert_eq!(ds.dtype()?.to_descriptor()?, Complex64::type_descriptor());
+
+    let as_c32 = ds.read_raw::<Complex32>()?;
+    assert_eq!(as_c32, vec![Complex32::new(1.0, -2.0), Complex32::new(0.5, 3.25)]);
+    Ok(())
+}
+
+#[test]
+fn test_read_write_matrix() -> hdf5::Result<()> {
+    use hdf5::types::DynValue;
+    use hdf5::H5Type;
+
+    #[derive(H5Type, Clone, Copy, Debug, PartialEq)]
+    #[repr(C)]
+    struct Pose {
+        id: u32,
+        rotation: [[f64; 3]; 3],
+    }
+
+    let file = new_in_memory_file()?;
+    let poses = [
+        Pose { id: 1, rotation: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]] },
+        Pose { id: 2, rotation: [[0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]] },
+    ];
+    let ds = file.new_dataset_builder().with_data(&poses).create("poses")?;
+    assert_eq!(ds.read_raw::<Pose>()?, poses);
+
+    let desc = ds.dtype()?.to_descriptor()?;
+    assert_eq!(desc, Pose::type_descriptor());
+    let value = hdf5::types::OwnedDynValue::new(poses[1]);
+    if let DynValue::Compound(pose) = value.get() {
+        let (name, rotation) = pose.iter().nth(1).unwrap();
+        assert_eq!(name, "rotation");
+        match rotation {
+            DynValue::Array(arr) => assert_eq!(arr.shape(), vec![3, 3]),
+            _ => panic!("expected an array"),
+        }
+    } else {
+        panic!("expected a compound");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_write_big_endian() -> hdf5::Result<()> {
+    use hdf5::datatype::ByteOrder;
+
+    let file = new_in_memory_file()?;
+    let values = [1.5_f64, -2.25, 1e10];
+    let ds = file
+        .new_dataset_builder()
+        .byte_order(ByteOrder::BigEndian)
+        .with_data(&values)
+        .create("be")?;
+    assert_eq!(ds.dtype()?.byte_order(), ByteOrder::BigEndian);
+    assert_eq!(ds.read_raw::<f64>()?, values);
+
+    let ds = file.new_dataset::<u16>().byte_order(ByteOrder::BigEndian).shape(2).create("u16")?;
+    ds.write(&[0x0102_u16, 0xfffe])?;
+    assert_eq!(ds.dtype()?.byte_order(), ByteOrder::BigEndian);
+    assert_eq!(ds.read_raw::<u16>()?, vec![0x0102, 0xfffe]);
+    assert_eq!(ds.read_raw::<u32>()?, vec![0x0102, 0xfffe]);
+
+    let attr = ds.new_attr::<i64>().byte_order(ByteOrder::BigEndian).create("attr")?;
+    attr.write_scalar(&-42_i64)?;
+    assert_eq!(attr.dtype()?.byte_order(), ByteOrder::BigEndian);
+    assert_eq!(attr.read_scalar::<i64>()?, -42);
+    Ok(())
+}
+
+#[test]
+fn test_space_padded_strings() -> hdf5::Result<()> {
+    use hdf5::types::{FixedAscii, StringPadding, TypeDescriptor as TD};
+
+    let file = new_in_memory_file()?;
+    let data = ["foo", "bar  ", ""].map(|s| FixedAscii::<8>::from_ascii(s).unwrap());
+    let desc = TD::FixedAscii(8, StringPadding::SpacePad);
+    let ds = file.new_dataset_builder().with_data_as(&data, &desc).create("padded")?;
+    assert_eq!(ds.dtype()?.to_descriptor()?, desc);
+
+    // on-disk values are space-padded, trailing spaces are not significant
+    let out = ds.read_raw::<FixedAscii<8>>()?;
+    assert_eq!(out.iter().map(|s| s.as_str()).collect::<Vec<_>>(), ["foo", "bar", ""]);
+    Ok(())
+}
+
+#[test]
+fn test_read_strings() -> hdf5::Result<()> {
+    use std::str::FromStr;
+
+    use hdf5::types::TypeDescriptor as TD;
+    use hdf5::types::{FixedAscii, FixedUnicode, StringPadding, VarLenAscii, VarLenUnicode};
+
+    let file = new_in_memory_file()?;
+    let words = ["foo", "", "bazbar", "x"];
+
+    let fa = words.map(|s| FixedAscii::<6>::from_ascii(s).unwrap());
+    file.new_dataset_builder().with_data(&fa).create("fa")?;
+    let fu = ["ö", "", "日本", "x"].map(|s| FixedUnicode::<9>::from_str(s).unwrap());
+    file.new_dataset_builder().with_data(&fu).create("fu")?;
+    let va = words.map(|s| VarLenAscii::from_ascii(s).unwrap());
+    file.new_dataset_builder().with_data(&va).create("va")?;
+    let vu = words.map(|s| VarLenUnicode::from_str(s).unwrap());
+    file.new_dataset_builder().with_data(&vu).create("vu")?;
+    let desc = TD::FixedAscii(10, StringPadding::SpacePad);
+    file.new_dataset_builder().with_data_as(&fa, &desc).create("sp")?;
+
+    for name in &["fa", "va", "vu", "sp"] {
+        let ds = file.dataset(name)?;
+        assert_eq!(ds.read_strings()?.into_raw_vec(), words);
+        assert_eq!(ds.read_strings_slice(1..3)?.into_raw_vec(), &words[1..3]);
+        assert_eq!(ds.as_reader().read_strings()?.shape(), &[4]);
+    }
+    assert_eq!(file.dataset("fu")?.read_strings()?.into_raw_vec(), ["ö", "", "日本", "x"]);
+
+    let arr =
+        ndarray::arr2(&[["a", "bc"], ["def", "g"]]).map(|s| VarLenUnicode::from_str(s).unwrap());
+    let ds = file.new_dataset_builder().with_data(&arr).create("2d")?;
+    assert_eq!(ds.read_strings()?, arr.map(|s| s.to_string()).into_dyn());
+    assert_eq!(ds.read_strings_slice(s![1, ..])?.into_raw_vec(), ["def", "g"]);
+
+    let attr = ds.new_attr_builder().with_data(&fa).create("attr")?;
+    assert_eq!(attr.read_strings()?.into_raw_vec(), words);
+    let attr = ds.new_attr::<VarLenAscii>().create("scalar")?;
+    attr.write_scalar(&VarLenAscii::from_ascii("scalar").unwrap())?;
+    assert_eq!(attr.read_strings()?.into_raw_vec(), ["scalar"]);
+
+    let ds = file.new_dataset_builder().with_data(&[1, 2, 3]).create("ints")?;
+    assert_err!(ds.read_strings(), "Expected a string datatype");
+    Ok(())
+}
+
+#[test]
+fn test_write_dyn_values() -> hdf5::Result<()> {
+    use hdf5::types::{
+        CompoundField, CompoundType, DynValueBuilder, IntSize, OwnedDynValue, VarLenUnicode,
+    };
+    use hdf5::H5Type;
+
+    #[derive(hdf5::H5Type, Clone, Debug, PartialEq)]
+    #[repr(C)]
+    struct Record {
+        id: u32,
+        kind: Enum,
+        name: VarLenUnicode,
+        pos: [f64; 2],
+    }
+
+    // schema known at runtime only, with fields in a different order
+    let desc = TypeDescriptor::Compound(CompoundType {
+        fields: vec![
+            CompoundField::new("pos", <[f64; 2]>::type_descriptor(), 0, 0),
+            CompoundField::new("name", TypeDescriptor::VarLenUnicode, 16, 1),
+            CompoundField::new("kind", Enum::type_descriptor(), 24, 2),
+            CompoundField::new("id", TypeDescriptor::Unsigned(IntSize::U4), 28, 3),
+        ],
+        size: 32,
+    });
+    let values = (0..3_u32)
+        .map(|i| {
+            let pos = vec![OwnedDynValue::new(i as f64), OwnedDynValue::new(-(i as f64))];
+            let pos = OwnedDynValue::from_elements(<[f64; 2]>::type_descriptor(), pos)?;
+            let kind = OwnedDynValue::from_enum_member(Enum::type_descriptor(), "Y")?;
+            let value = DynValueBuilder::new(desc.clone())
+                .field("id", i)?
+                .field("kind", kind)?
+                .field("name", OwnedDynValue::from_str_as(TypeDescriptor::VarLenAscii, "rec")?)?
+                .field("pos", pos)?
+                .finish();
+            Ok(value)
+        })
+        .collect::<hdf5::Result<Vec<_>>>()?;
+
+    let file = new_in_memory_file()?;
+    let ds = file.new_dataset::<Record>().shape(3).create("records")?;
+    ds.write_dyn_values(&values)?;
+    let records = ds.read_raw::<Record>()?;
+    for (i, record) in records.iter().enumerate() {
+        assert_eq!(record.id, i as u32);
+        assert_eq!(record.kind, Enum::Y);
+        assert_eq!(record.name.as_str(), "rec");
+        assert_eq!(record.pos, [i as f64, -(i as f64)]);
+    }
+
+    let attr = ds.new_attr_builder().empty_as(&desc).shape(1).create("attr")?;
+    attr.write_dyn_values(&values[1..2])?;
+    assert_eq!(attr.read_raw::<Record>()?, &records[1..2]);
+
+    assert_err!(ds.write_dyn_values(&values[..2]), "length mismatch");
+    let mixed = vec![values[0].clone(), OwnedDynValue::new(1_u32), values[2].clone()];
+    assert_err!(ds.write_dyn_values(&mixed), "type mismatch");
+    Ok(())
+}
+
+#[test]
+fn test_read_dyn_values() -> hdf5::Result<()> {
+    use hdf5::types::{DynValue, OwnedDynValue, VarLenArray, VarLenUnicode};
+
+    #[derive(hdf5::H5Type, Clone, Debug, PartialEq)]
+    #[repr(C)]
+    struct Record {
+        id: u32,
+        kind: Enum,
+        name: VarLenUnicode,
+        data: VarLenArray<i32>,
+    }
+
+    let records = ndarray::Array2::from_shape_fn((3, 2), |(i, j)| Record {
+        id: (i * 2 + j) as _,
+        kind: if j == 0 { Enum::X } else { Enum::Y },
+        name: format!("record {}", i * 2 + j).parse().unwrap(),
+        data: VarLenArray::from_slice(&vec![i as i32; j + 1]),
+    });
+    let file = new_in_memory_file()?;
+    let ds = file.new_dataset_builder().with_data(&records).create("records")?;
+
+    let values = ds.read_dyn_values()?;
+    assert_eq!(values.shape(), &[3, 2]);
+    for (value, record) in values.iter().zip(records.iter()) {
+        assert_eq!(value.type_descriptor(), &<Record as hdf5::H5Type>::type_descriptor());
+        assert_eq!(value, &OwnedDynValue::new(record.clone()));
+    }
+    assert_eq!(values[[2, 1]].clone().cast::<Record>().unwrap(), records[[2, 1]]);
+    drop(values);
+
+    let values = ds.read_dyn_values_slice(s![1.., 1])?;
+    assert_eq!(values.shape(), &[2]);
+    assert_eq!(values[1], OwnedDynValue::new(records[[2, 1]].clone()));
+    if let DynValue::Compound(record) = values[0].get() {
+        let (name, value) = record.iter().nth(2).unwrap();
+        assert_eq!(name, "name");
+        assert_eq!(format!("{}", value), "\"record 3\"");
+    } else {
+        panic!("expected a compound value");
+    }
+
+    #[derive(hdf5::H5Type, Clone, Debug, PartialEq)]
+    #[repr(C)]
+    struct Tagged {
+        tag: u8,
+        name: VarLenUnicode,
+        value: f64,
+    }
+
+    let tagged = (0..3)
+        .map(|i| Tagged { tag: i, name: format!("tag {}", i).parse().unwrap(), value: -0.5 })
+        .collect::<Vec<_>>();
+    let packed = file.new_dataset_builder().packed(true).with_data(&tagged).create("packed")?;
+    assert_eq!(packed.dtype()?.size(), 1 + std::mem::size_of::<VarLenUnicode>() + 8);
+    let values = packed.read_dyn_values()?;
+    for (value, tagged) in values.iter().zip(tagged.iter()) {
+        assert_eq!(value.type_descriptor(), &<Tagged as hdf5::H5Type>::type_descriptor());
+        assert_eq!(value, &OwnedDynValue::new(tagged.clone()));
+    }
+    drop(values);
+
+    let attr = ds.new_attr::<f64>().create("scale")?;
+    attr.write_scalar(&0.5)?;
+    let values = attr.read_dyn_values()?;
+    assert_eq!(values.ndim(), 0);
+    assert_eq!(values.first().unwrap(), &OwnedDynValue::new(0.5));
+    Ok(())
+}
+
+#[test]
+fn test_read_odd_size_integers() -> hdf5::Result<()> {
+    use hdf5::types::{CompoundField, CompoundType, OwnedDynValue};
+    use hdf5::{from_id, Dataset, Dataspace, Datatype, Location};
+    use hdf5_sys::{h5d::H5Dcreate2, h5p::H5P_DEFAULT, h5t};
+    use hdf5_types::IntSize;
+
+    fn int_type<T: hdf5::H5Type>(size: usize, precision: usize) -> hdf5::Result<Datatype> {
+        let base = Datatype::from_type::<T>()?;
+        unsafe {
+            let dtype = from_id::<Datatype>(h5t::H5Tcopy(base.id()))?;
+            h5t::H5Tset_size(dtype.id(), size);
+            h5t::H5Tset_precision(dtype.id(), precision);
+            Ok(dtype)
+        }
+    }
+
+    fn create_dataset<T: hdf5::H5Type>(
+        loc: &Location, name: &str, size: usize, precision: usize,
+    ) -> hdf5::Result<Dataset> {
+        create_dataset_with_type(loc, name, &int_type::<T>(size, precision)?)
+    }
+
+    fn create_dataset_with_type(
+        loc: &Location, name: &str, dtype: &Datatype,
+    ) -> hdf5::Result<Dataset> {
+        let space = Dataspace::try_new(4)?;
+        let name = std::ffi::CString::new(name).unwrap();
+        unsafe {
+            let id = H5Dcreate2(
+                loc.id(),
+                name.as_ptr(),
+                dtype.id(),
+                space.id(),
+                H5P_DEFAULT,
+                H5P_DEFAULT,
+                H5P_DEFAULT,
+            );
+            from_id(id)
+        }
+    }
+
+    let file = new_in_memory_file()?;
+
+    let i24 = create_dataset::<i32>(&file, "i24", 3, 24)?;
+    assert_eq!(i24.dtype()?.to_descriptor()?, TypeDescriptor::Integer(IntSize::U4));
+    i24.write(&[-8_388_608, -1, 0, 8_388_607])?;
+    assert_eq!(i24.read_raw::<i32>()?, vec![-8_388_608, -1, 0, 8_388_607]);
+    assert_eq!(i24.read_raw::<i64>()?, vec![-8_388_608, -1, 0, 8_388_607]);
+
+    let u12 = create_dataset::<u16>(&file, "u12", 2, 12)?;
+    assert_eq!(u12.dtype()?.precision(), 12);
+    assert_eq!(u12.dtype()?.to_descriptor()?, TypeDescriptor::Unsigned(IntSize::U2));
+    u12.write(&[0_u16, 1, 2048, 4095])?;
+    assert_eq!(u12.read_raw::<u16>()?, vec![0, 1, 2048, 4095]);
+    assert_eq!(u12.read_raw::<u32>()?, vec![0, 1, 2048, 4095]);
+
+    #[derive(hdf5::H5Type, Clone, Copy, Debug, PartialEq)]
+    #[repr(C)]
+    struct Row {
+        flag: u8,
+        value: i32,
+        tag: u8,
+    }
+
+    // packed compound with a 3-byte integer member: {flag: u8 @ 0, value: i24 @ 1, tag: u8 @ 4}
+    let compound = unsafe {
+        let dtype = from_id::<Datatype>(h5t::H5Tcreate(h5t::H5T_class_t::H5T_COMPOUND, 5))?;
+        let u8_type = Datatype::from_type::<u8>()?;
+        let i24_type = int_type::<i32>(3, 24)?;
+        for &(name, offset, member) in
+            &[("flag", 0, &u8_type), ("value", 1, &i24_type), ("tag", 4, &u8_type)]
+        {
+            let name = std::ffi::CString::new(name).unwrap();
+            h5t::H5Tinsert(dtype.id(), name.as_ptr(), offset, member.id());
+        }
+        dtype
+    };
+    let rows = create_dataset_with_type(&file, "rows", &compound)?;
+    let desc = rows.dtype()?.to_descriptor()?;
+    let field =
+        |name: &str, ty, offset, index| CompoundField { name: name.to_owned(), ty, offset, index };
+    let expected = TypeDescriptor::Compound(CompoundType {
+        fields: vec![
+            field("flag", TypeDescriptor::Unsigned(IntSize::U1), 0, 0),
+            field("value", TypeDescriptor::Integer(IntSize::U4), 4, 1),
+            field("tag", TypeDescriptor::Unsigned(IntSize::U1), 8, 2),
+        ],
+        size: 12,
+    });
+    assert_eq!(desc, expected);
+    assert_eq!(desc, <Row as hdf5::H5Type>::type_descriptor());
+    assert!(Datatype::from_descriptor(&desc).is_ok());
+
+    let data: Vec<_> = (0..4).map(|i| Row { flag: i, value: -1 << (i * 5), tag: 9 - i }).collect();
+    rows.write(&data)?;
+    assert_eq!(rows.read_raw::<Row>()?, data);
+    assert_eq!(rows.read_dyn_values()?[3], OwnedDynValue::new(data[3]));
+    Ok(())
+}
+
+#[test]
+fn test_read_write_matrix_layouts() -> hdf5::Result<()> {
+    use hdf5::types::OwnedDynValue;
+    use hdf5::{from_id, Dataset, Dataspace, Datatype, H5Type};
+    use hdf5_sys::{h5d::H5Dcreate2, h5p::H5P_DEFAULT, h5t::H5Tarray_create2};
+
+    let file = new_in_memory_file()?;
+    let create_dataset = |name: &str, dtype: &Datatype| -> hdf5::Result<Dataset> {
+        let space = Dataspace::try_new(2)?;
+        let name = std::ffi::CString::new(name).unwrap();
+        let (fid, tid, sid) = (file.id(), dtype.id(), space.id());
+        unsafe {
+            let id =
+                H5Dcreate2(fid, name.as_ptr(), tid, sid, H5P_DEFAULT, H5P_DEFAULT, H5P_DEFAULT);
+            from_id(id)
+        }
+    };
+    let f64_type = Datatype::from_type::<f64>()?;
+    let matrices = [[[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]], [[-1.0, -2.0, -3.0], [-4.0, -5.0, -6.0]]];
+
+    // array of arrays, as written by previous versions
+    let nested = unsafe {
+        let row = from_id::<Datatype>(H5Tarray_create2(f64_type.id(), 1, [3].as_ptr()))?;
+        from_id::<Datatype>(H5Tarray_create2(row.id(), 1, [2].as_ptr()))?
+    };
+    let ds = create_dataset("nested", &nested)?;
+    ds.write(&matrices)?;
+    assert_eq!(ds.read_raw::<[[f64; 3]; 2]>()?, matrices);
+    assert_eq!(ds.dtype()?.to_descriptor()?, <[[f64; 3]; 2] as H5Type>::type_descriptor());
+
+    // single two-dimensional array, as written e.g. by numpy for subarrays
+    let matrix =
+        unsafe { from_id::<Datatype>(H5Tarray_create2(f64_type.id(), 2, [2, 3].as_ptr()))? };
+    let ds = create_dataset("matrix", &matrix)?;
+    ds.write(&matrices)?;
+    assert_eq!(ds.read_raw::<[[f64; 3]; 2]>()?, matrices);
+    assert_eq!(ds.read_dyn_values()?[1], OwnedDynValue::new(matrices[1]));
+    assert!(ds.read_raw::<[[f64; 2]; 3]>().is_err());
+
+    // the default layout for nested arrays stays the same
+    let ds = file.new_dataset_builder().with_data(&matrices).create("default")?;
+    assert!(ds.dtype()? == nested);
+    Ok(())
+}
+
 #[test]
 fn test_read_write_enum() -> hdf5::Result<()> {
     test_read_write::<Enum>()
@@ -250,6 +707,11 @@ fn test_read_write_varlen_struct() -> hdf5::Result<()> {
     test_read_write::<VarLenStruct>()
 }
 
+#[test]
+fn test_read_write_raw_struct() -> hdf5::Result<()> {
+    test_read_write::<RawStruct>()
+}
+
 #[test]
 fn test_read_write_tuples() -> hdf5::Result<()> {
     test_read_write::<(u8,)>()?;
@@ -267,3 +729,223 @@ fn test_create_on_databuilder() {
     let _ds = file.new_dataset::<i32>().create("ds3").unwrap();
     let _ds = file.new_dataset::<i32>().shape(2).create("ds4").unwrap();
 }
+
+#[test]
+fn test_read_compound_fields() -> hdf5::Result<()> {
+    use hdf5::types::VarLenUnicode;
+    use hdf5::Conversion;
+
+    #[derive(hdf5::H5Type, Clone, Debug, PartialEq)]
+    #[repr(C)]
+    struct Row {
+        id: u32,
+        x: f64,
+        name: VarLenUnicode,
+        flag: bool,
+        y: i16,
+    }
+
+    #[derive(hdf5::H5Type, Clone, Debug, PartialEq)]
+    #[repr(C)]
+    struct Subset {
+        y: i64,
+        id: u32,
+    }
+
+    #[derive(hdf5::H5Type, Clone, Debug, PartialEq)]
+    #[repr(C)]
+    struct Missing {
+        id: u32,
+        z: f32,
+    }
+
+    let rows = Array1::from_shape_fn(5, |i| Row {
+        id: i as _,
+        x: i as f64 / 2.,
+        name: format!("row {}", i).parse().unwrap(),
+        flag: i % 2 == 0,
+        y: -(i as i16),
+    });
+    let file = new_in_memory_file()?;
+    let ds = file.new_dataset_builder().with_data(&rows).create("rows")?;
+
+    let subset = ds.read_1d::<Subset>()?;
+    assert_eq!(subset.len(), 5);
+    for (row, subset) in rows.iter().zip(subset.iter()) {
+        assert_eq!(subset, &Subset { y: row.y as _, id: row.id });
+    }
+    assert_err!(
+        ds.as_reader().conversion(Conversion::Hard).read_raw::<Subset>(),
+        "hard conversion path required; available: soft conversion"
+    );
+    assert_err!(ds.read_1d::<Missing>(), "Field \"z\" not found in the source compound datatype");
+    assert_err!(ds.as_reader().no_convert().read_raw::<Subset>(), "Field \"y\": ");
+
+    assert_eq!(ds.read_field::<f64>("x")?.as_slice().unwrap(), &[0., 0.5, 1., 1.5, 2.]);
+    assert_eq!(
+        ds.read_field::<bool>("flag")?.as_slice().unwrap(),
+        &[true, false, true, false, true]
+    );
+    let names = ds.read_field_slice::<VarLenUnicode, _>("name", s![1..3])?;
+    assert_eq!(names.as_slice().unwrap(), &[rows[1].name.clone(), rows[2].name.clone()]);
+    assert_eq!(ds.read_field_slice::<i32, _>("y", s![3..])?.as_slice().unwrap(), &[-3, -4]);
+    assert_err!(ds.read_field::<u8>("z"), "Field \"z\" not found in the source compound datatype");
+
+    let scalars = file.new_dataset::<u32>().shape(3).create("scalars")?;
+    assert_err!(
+        scalars.read_field::<u32>("id"),
+        "Cannot read field \"id\" of a non-compound datatype"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_read_write_nested_varlen() -> hdf5::Result<()> {
+    use hdf5::types::{VarLenArray, VarLenUnicode};
+
+    let file = new_in_memory_file()?;
+
+    let words = |w: &[&str]| -> VarLenArray<VarLenUnicode> {
+        w.iter().map(|s| s.parse().unwrap()).collect::<Vec<_>>().into()
+    };
+    let strings = Array1::from(vec![words(&["a", "bc"]), words(&[]), words(&["def"])]);
+    let ds = file.new_dataset_builder().with_data(&strings).create("strings")?;
+    assert_eq!(ds.read_1d::<VarLenArray<VarLenUnicode>>()?, strings);
+
+    let floats = Array1::from(vec![
+        VarLenArray::from(vec![VarLenArray::from([1.0_f32, 2.0]), VarLenArray::default()]),
+        VarLenArray::default(),
+        VarLenArray::from(vec![VarLenArray::from([3.0_f32])]),
+    ]);
+    let ds = file.new_dataset_builder().with_data(&floats).create("floats")?;
+    let read = ds.read_1d::<VarLenArray<VarLenArray<f32>>>()?;
+    assert_eq!(read, floats);
+    assert_eq!(read[0][0].as_slice(), &[1.0, 2.0]);
+
+    let attr = ds.new_attr::<VarLenArray<VarLenUnicode>>().create("words")?;
+    attr.write_scalar(&strings[0])?;
+    assert_eq!(attr.read_scalar::<VarLenArray<VarLenUnicode>>()?, strings[0]);
+    Ok(())
+}
+
+#[test]
+fn test_read_write_timestamps() -> hdf5::Result<()> {
+    use hdf5::types::{TimeDelta, Timestamp, VarLenUnicode, TIMESTAMP_UNITS, TIME_DELTA_UNITS};
+    use hdf5::H5Type;
+
+    let file = new_in_memory_file()?;
+    let units = |ds: &hdf5::Dataset| -> hdf5::Result<String> {
+        Ok(ds.attr("units")?.read_scalar::<VarLenUnicode>()?.as_str().to_owned())
+    };
+
+    let times = Array1::from_shape_fn(4, |i| Timestamp::from_unix_nanos(i as i64 * 1_000 - 1_500));
+    let ds = file.new_dataset_builder().with_data(&times).create("times")?;
+    assert_eq!(units(&ds)?, TIMESTAMP_UNITS);
+    assert_eq!(ds.read_1d::<Timestamp>()?, times);
+    assert_eq!(ds.read_1d::<i64>()?.as_slice().unwrap(), &[-1_500, -500, 500, 1_500]);
+
+    let ds = file.new_dataset::<TimeDelta>().shape(2).create("deltas")?;
+    assert_eq!(units(&ds)?, TIME_DELTA_UNITS);
+    ds.write(&[TimeDelta::from_nanos(5), TimeDelta::from_nanos(-5)])?;
+    assert_eq!(ds.read_raw::<i64>()?, vec![5, -5]);
+
+    let ds = file.new_dataset::<i64>().shape(1).create("plain")?;
+    assert!(ds.attr("units").is_err());
+
+    // explicit units take precedence and also apply to other types
+    let ds = file.new_dataset::<Timestamp>().units("ns since boot").shape(1).create("boot")?;
+    assert_eq!(units(&ds)?, "ns since boot");
+    let ds = file
+        .new_dataset_builder()
+        .units(TIMESTAMP_UNITS)
+        .with_data(&[[Timestamp::UNIX_EPOCH; 2]])
+        .create("pairs")?;
+    assert_eq!(units(&ds)?, TIMESTAMP_UNITS);
+    let ds = file.new_dataset_builder().empty_as(&i64::type_descriptor()).units("m").create("m")?;
+    assert_eq!(units(&ds)?, "m");
+    Ok(())
+}
+
+#[test]
+fn test_read_write_packed() -> hdf5::Result<()> {
+    #[derive(hdf5::H5Type, Clone, Copy, Debug, PartialEq)]
+    #[repr(C, packed)]
+    struct Packed {
+        id: u8,
+        value: f64,
+        flags: u16,
+    }
+
+    #[derive(hdf5::H5Type, Clone, Copy, Debug, PartialEq)]
+    #[repr(C)]
+    struct Aligned {
+        flags: u16,
+        value: f64,
+        id: u8,
+    }
+
+    let records: Vec<_> =
+        (0..4).map(|i| Packed { id: i as _, value: i as f64 * 0.5, flags: 1 << i }).collect();
+    let file = new_in_memory_file()?;
+    let ds = file.new_dataset_builder().packed(true).with_data(&records).create("records")?;
+    assert_eq!(ds.dtype()?.size(), 11);
+    assert_eq!(ds.dtype()?.to_descriptor()?, <Packed as hdf5::H5Type>::type_descriptor());
+    assert_eq!(ds.read_raw::<Packed>()?, records);
+    let aligned = ds.read_raw::<Aligned>()?;
+    assert_eq!(aligned[3], Aligned { flags: 8, value: 1.5, id: 3 });
+
+    // by default, the file type is re-aligned like a `repr(C)` struct
+    let ds = file.new_dataset_builder().with_data(&records).create("aligned")?;
+    assert_eq!(ds.dtype()?.size(), 24);
+    assert_eq!(ds.read_raw::<Packed>()?, records);
+    Ok(())
+}
+
+#[test]
+fn test_read_missing_fields_with_defaults() -> hdf5::Result<()> {
+    #[derive(hdf5::H5Type, Clone, Copy, Debug, PartialEq)]
+    #[repr(C)]
+    struct V1 {
+        id: u32,
+        value: f32,
+    }
+
+    #[derive(hdf5::H5Type, Clone, Copy, Debug, PartialEq)]
+    #[repr(C)]
+    struct V2 {
+        #[hdf5(default = 42)]
+        version: u8,
+        value: f64,
+        #[hdf5(default)]
+        flags: u16,
+        id: u64,
+        #[hdf5(rename = "scale", default = 1.0)]
+        factor: f32,
+    }
+
+    #[derive(hdf5::H5Type, Clone, Copy, Debug, PartialEq)]
+    #[repr(C)]
+    struct V3 {
+        id: u32,
+        name: u8,
+    }
+
+    let v2 = |id, value| V2 { version: 42, value, flags: 0, id, factor: 1.0 };
+
+    let file = new_in_memory_file()?;
+    let records: Vec<_> = (0..4).map(|i| V1 { id: i, value: i as f32 * 0.5 }).collect();
+    let ds = file.new_dataset_builder().with_data(&records).create("records")?;
+    assert_eq!(ds.read_raw::<V2>()?, (0..4).map(|i| v2(i, i as f64 * 0.5)).collect::<Vec<_>>());
+    assert_eq!(ds.read_slice_1d::<V2, _>(2..)?.to_vec(), vec![v2(2, 1.0), v2(3, 1.5)]);
+    assert!(ds.read_raw::<V3>().is_err());
+
+    let ds = file.new_dataset::<V1>().create("scalar")?;
+    ds.write_scalar(&V1 { id: 7, value: -2.0 })?;
+    assert_eq!(ds.read_scalar::<V2>()?, v2(7, -2.0));
+
+    // fields present in the file are never overwritten by the defaults
+    let current = V2 { version: 3, value: 2.0, flags: 5, id: 1, factor: 0.5 };
+    let ds = file.new_dataset_builder().with_data(&[current]).create("current")?;
+    assert_eq!(ds.read_raw::<V2>()?, vec![current]);
+    Ok(())
+}
@@ -1,11 +1,14 @@
 #[macro_use]
 mod common;
 
+use hdf5::datatype::ByteOrder;
 use hdf5::types::{TypeDescriptor as TD, *};
 use hdf5::{from_id, Datatype, H5Type};
 
 use hdf5_sys::h5i::H5I_INVALID_HID;
 
+use self::common::util::new_in_memory_file;
+
 macro_rules! check_roundtrip {
     ($ty:ty, $desc:expr) => {{
         let desc = <$ty as H5Type>::type_descriptor();
@@ -28,13 +31,20 @@ pub fn test_datatype_roundtrip() {
     check_roundtrip!(u64, TD::Unsigned(IntSize::U8));
     check_roundtrip!(f32, TD::Float(FloatSize::U4));
     check_roundtrip!(f64, TD::Float(FloatSize::U8));
+    #[cfg(feature = "half")]
+    check_roundtrip!(half::f16, TD::Float(FloatSize::U2));
     check_roundtrip!(bool, TD::Boolean);
     check_roundtrip!([bool; 5], TD::FixedArray(Box::new(TD::Boolean), 5));
     check_roundtrip!(VarLenArray<bool>, TD::VarLenArray(Box::new(TD::Boolean)));
-    check_roundtrip!(FixedAscii<5>, TD::FixedAscii(5));
-    check_roundtrip!(FixedUnicode<5>, TD::FixedUnicode(5));
+    check_roundtrip!(FixedAscii<5>, TD::FixedAscii(5, StringPadding::NullPad));
+    check_roundtrip!(FixedUnicode<5>, TD::FixedUnicode(5, StringPadding::NullPad));
     check_roundtrip!(VarLenAscii, TD::VarLenAscii);
     check_roundtrip!(VarLenUnicode, TD::VarLenUnicode);
+    check_roundtrip!(Opaque<7>, TD::Opaque { size: 7, tag: "".into() });
+    check_roundtrip!(Bitfield<u8>, TD::Bitfield(IntSize::U1));
+    check_roundtrip!(Bitfield<u16>, TD::Bitfield(IntSize::U2));
+    check_roundtrip!(Bitfield<u32>, TD::Bitfield(IntSize::U4));
+    check_roundtrip!(Bitfield<u64>, TD::Bitfield(IntSize::U8));
 
     #[allow(dead_code)]
     #[derive(H5Type)]
@@ -86,6 +96,224 @@ pub fn test_datatype_roundtrip() {
     check_roundtrip!(C, c_desc);
 }
 
+#[test]
+pub fn test_multidim_array_roundtrip() {
+    let matrix = TD::FixedArray(Box::new(TD::FixedArray(Box::new(TD::Float(FloatSize::U8)), 3)), 3);
+    check_roundtrip!([[f64; 3]; 3], matrix);
+    let cube = TD::FixedArray(
+        Box::new(TD::FixedArray(Box::new(TD::FixedArray(Box::new(TD::Boolean), 4)), 3)),
+        2,
+    );
+    check_roundtrip!([[[bool; 4]; 3]; 2], cube);
+
+    // nested arrays are still stored as arrays of arrays
+    let dt = Datatype::from_type::<[[f64; 3]; 3]>().unwrap();
+    assert_eq!(unsafe { hdf5_sys::h5t::H5Tget_array_ndims(dt.id()) }, 1);
+}
+
+#[test]
+pub fn test_opaque_tag_roundtrip() {
+    let desc = TD::Opaque { size: 16, tag: "vendor:frame-header".into() };
+    let dt = Datatype::from_descriptor(&desc).unwrap();
+    assert_eq!(dt.to_descriptor().unwrap(), desc);
+    assert_eq!(dt.size(), 16);
+
+    let untagged = Datatype::from_type::<Opaque<16>>().unwrap();
+    assert_ne!(dt, untagged);
+}
+
+#[test]
+pub fn test_string_padding() {
+    use hdf5_sys::h5t::{H5T_str_t, H5Tget_strpad};
+
+    let cases = [
+        (StringPadding::NullTerm, H5T_str_t::H5T_STR_NULLTERM),
+        (StringPadding::NullPad, H5T_str_t::H5T_STR_NULLPAD),
+        (StringPadding::SpacePad, H5T_str_t::H5T_STR_SPACEPAD),
+    ];
+    for &(padding, strpad) in &cases {
+        for desc in &[TD::FixedAscii(7, padding), TD::FixedUnicode(7, padding)] {
+            let dt = Datatype::from_descriptor(desc).unwrap();
+            assert_eq!(unsafe { H5Tget_strpad(dt.id()) }, strpad);
+            assert_eq!(dt.to_descriptor().unwrap(), *desc);
+        }
+    }
+    let vlen = Datatype::from_type::<VarLenAscii>().unwrap();
+    assert_eq!(unsafe { H5Tget_strpad(vlen.id()) }, H5T_str_t::H5T_STR_NULLTERM);
+}
+
+fn custom_integer<T: H5Type>(size: usize, precision: usize, offset: usize) -> Datatype {
+    use hdf5_sys::h5t::{H5Tcopy, H5Tset_offset, H5Tset_precision, H5Tset_size};
+
+    let base = Datatype::from_type::<T>().unwrap();
+    unsafe {
+        let id = H5Tcopy(base.id());
+        assert!(H5Tset_size(id, size) >= 0);
+        assert!(H5Tset_precision(id, precision) >= 0);
+        assert!(H5Tset_offset(id, offset) >= 0);
+        from_id(id).unwrap()
+    }
+}
+
+#[test]
+pub fn test_odd_integer_sizes() {
+    let i24 = custom_integer::<i32>(3, 24, 0);
+    assert_eq!(i24.size(), 3);
+    assert_eq!(i24.precision(), 24);
+    assert_eq!(i24.to_descriptor().unwrap(), TD::Integer(IntSize::U4));
+    assert!(i24.conv_to::<i32>().is_some());
+    assert!(i24.conv_to::<i64>().is_some());
+
+    let u12 = custom_integer::<u16>(2, 12, 4);
+    assert_eq!(u12.size(), 2);
+    assert_eq!(u12.precision(), 12);
+    assert_eq!(u12.to_descriptor().unwrap(), TD::Unsigned(IntSize::U2));
+    assert_ne!(u12, Datatype::from_type::<u16>().unwrap());
+    assert!(u12.conv_to::<u16>().is_some());
+
+    let u40 = custom_integer::<u64>(5, 40, 0);
+    assert_eq!(u40.to_descriptor().unwrap(), TD::Unsigned(IntSize::U8));
+    assert_eq!(Datatype::from_type::<u32>().unwrap().precision(), 32);
+}
+
+#[test]
+pub fn test_float16_layout() {
+    use hdf5_sys::h5t::{H5Tcopy, H5Tset_ebias, H5Tset_fields, H5Tset_size};
+
+    let f16 = Datatype::from_descriptor(&TD::Float(FloatSize::U2)).unwrap();
+    assert_eq!(f16.size(), 2);
+    assert_eq!(f16.to_descriptor().unwrap(), TD::Float(FloatSize::U2));
+
+    // bfloat16 (8 exponent bits, 7 mantissa bits) has the same size but a different layout
+    let base = Datatype::from_type::<f32>().unwrap();
+    let bf16: Datatype = unsafe {
+        let id = H5Tcopy(base.id());
+        assert!(H5Tset_fields(id, 15, 7, 8, 0, 7) >= 0);
+        assert!(H5Tset_size(id, 2) >= 0);
+        assert!(H5Tset_ebias(id, 127) >= 0);
+        from_id(id).unwrap()
+    };
+    assert_err!(bf16.to_descriptor(), "not IEEE half precision");
+}
+
+#[test]
+pub fn test_parse_type_descriptor() {
+    #[derive(H5Type)]
+    #[repr(C)]
+    struct A {
+        a: [i16; 2],
+        b: VarLenUnicode,
+        c: FixedAscii<3>,
+    }
+    let desc = A::type_descriptor();
+    let parsed: TD = format!("{:#}", desc).parse().unwrap();
+    assert_eq!(parsed, desc);
+    let dt = Datatype::from_descriptor(&parsed).unwrap();
+    assert_eq!(dt, Datatype::from_type::<A>().unwrap());
+
+    let desc: TD =
+        "compound {a: [int16; 2], b: unicode (var len), c: string (len 3)}".parse().unwrap();
+    assert_eq!(desc, A::type_descriptor());
+
+    let err = "[uint32; 4".parse::<TD>().unwrap_err();
+    assert_eq!(err.position(), 10);
+    assert_eq!(
+        hdf5::Error::from(err).to_string(),
+        "invalid type descriptor at position 10: expected `]`, found end of input"
+    );
+}
+
+#[test]
+pub fn test_explicit_byte_order() {
+    let native = Datatype::from_type::<u32>().unwrap();
+    assert_eq!(native.byte_order(), ByteOrder::native());
+    let desc = u32::type_descriptor();
+    for &order in &[ByteOrder::LittleEndian, ByteOrder::BigEndian] {
+        let dt = Datatype::from_descriptor_with_order(&desc, order).unwrap();
+        assert_eq!(dt.byte_order(), order);
+        assert_eq!(dt.to_descriptor().unwrap(), desc);
+        assert_eq!(dt == native, order == ByteOrder::native());
+    }
+
+    #[derive(H5Type)]
+    #[repr(C)]
+    struct A {
+        a: [i16; 2],
+        b: f64,
+    }
+    let be = Datatype::from_descriptor_with_order(&A::type_descriptor(), ByteOrder::BigEndian);
+    let le = Datatype::from_descriptor_with_order(&A::type_descriptor(), ByteOrder::LittleEndian);
+    let (be, le) = (be.unwrap(), le.unwrap());
+    assert_ne!(be, le);
+    assert_eq!(be.to_descriptor().unwrap(), A::type_descriptor());
+    assert_eq!(le.to_descriptor().unwrap(), A::type_descriptor());
+
+    assert_err!(
+        Datatype::from_descriptor_with_order(&desc, ByteOrder::Vax),
+        "Unsupported byte order"
+    );
+}
+
+#[test]
+pub fn test_user_conversion() -> hdf5::Result<()> {
+    use hdf5::Conversion;
+    use hdf5_sys::h5t::{H5T_order_t, H5Tset_order};
+
+    // Conversion functions are process-global, so this test only registers them for
+    // types no other test uses: here, legacy temperatures stored as 24-bit big-endian
+    // hundredths of a kelvin.
+    let raw = custom_integer::<u32>(3, 24, 0);
+    assert!(unsafe { H5Tset_order(raw.id(), H5T_order_t::H5T_ORDER_BE) } >= 0);
+    let celsius = Datatype::from_type::<f32>()?;
+    Datatype::register_conversion("kelvin_to_celsius", &raw, &celsius, |src, dst| {
+        let kelvin = u32::from_be_bytes([0, src[0], src[1], src[2]]) as f32 / 100.;
+        dst.copy_from_slice(&(kelvin - 273.15).to_ne_bytes());
+    })?;
+    assert_eq!(raw.conv_path(&celsius), Some(Conversion::Soft));
+
+    let file = new_in_memory_file()?;
+    let committed = file.commit_datatype("centikelvin", &raw)?;
+    let ds = file
+        .new_dataset_builder()
+        .committed_type(&committed)
+        .with_data(&[27315_u32, 29315, 37315])
+        .create("temperatures")?;
+    assert_eq!(ds.dtype()?, raw);
+    let temperatures = ds.read_raw::<f32>()?;
+    for (t, expected) in temperatures.iter().zip(&[0., 20., 100.]) {
+        assert!((t - expected).abs() < 1e-3, "{} != {}", t, expected);
+    }
+    assert_err!(
+        ds.as_reader().conversion(Conversion::Hard).read_raw::<f32>(),
+        "hard conversion path required; available: soft conversion"
+    );
+
+    Datatype::unregister_conversion(&raw, &celsius)?;
+    assert_eq!(ds.read_raw::<f32>()?, vec![27315., 29315., 37315.]);
+    assert_err!(Datatype::unregister_conversion(&raw, &celsius), "No user-defined conversion");
+
+    #[allow(dead_code)]
+    #[derive(H5Type, Debug, PartialEq)]
+    #[repr(u8)]
+    enum UnitsV1 {
+        C = 0,
+        K = 1,
+    }
+    #[allow(dead_code)]
+    #[derive(H5Type, Debug, PartialEq)]
+    #[repr(u8)]
+    enum UnitsV2 {
+        Fahrenheit = 0,
+        Celsius = 1,
+        Kelvin = 2,
+    }
+    let (v1, v2) = (Datatype::from_type::<UnitsV1>()?, Datatype::from_type::<UnitsV2>()?);
+    Datatype::register_conversion("units_v1_to_v2", &v1, &v2, |src, dst| dst[0] = src[0] + 1)?;
+    let ds = file.new_dataset_builder().with_data(&[UnitsV1::K, UnitsV1::C]).create("units")?;
+    assert_eq!(ds.read_raw::<UnitsV2>()?, vec![UnitsV2::Kelvin, UnitsV2::Celsius]);
+    Datatype::unregister_conversion(&v1, &v2)
+}
+
 #[test]
 pub fn test_invalid_datatype() {
     assert_err!(from_id::<Datatype>(H5I_INVALID_HID), "Invalid handle id");
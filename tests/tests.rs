@@ -1,3 +1,8 @@
+#[macro_use]
+mod common;
+
+use self::common::util::new_in_memory_file;
+
 #[test]
 fn roundtrip_compound_type() {
     use hdf5::H5Type;
@@ -12,3 +17,119 @@ fn roundtrip_compound_type() {
     let td = dt.to_descriptor().unwrap();
     assert_eq!(td, Compound::type_descriptor());
 }
+
+#[test]
+fn roundtrip_attrs() -> hdf5::Result<()> {
+    use hdf5::types::VarLenUnicode;
+    use hdf5::{H5Attrs, H5Type};
+
+    #[derive(H5Type, Clone, Copy, Debug, PartialEq)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Mode {
+        Fast = 1,
+        Slow = 2,
+    }
+
+    #[derive(H5Attrs, Debug, PartialEq)]
+    struct Meta {
+        #[hdf5(rename = "Instrument")]
+        instrument: VarLenUnicode,
+        gain: f64,
+        mode: Mode,
+        offset: Option<[i32; 2]>,
+        #[hdf5(rename = "x.pos")]
+        x: Option<f32>,
+    }
+
+    let file = new_in_memory_file()?;
+    let ds = file.new_dataset::<u8>().create("data")?;
+    let meta = Meta {
+        instrument: "camera".parse().unwrap(),
+        gain: 1.5,
+        mode: Mode::Slow,
+        offset: Some([-1, 2]),
+        x: None,
+    };
+    meta.write_attrs(&ds)?;
+    let mut names = ds.attr_names()?;
+    names.sort();
+    assert_eq!(names, vec!["Instrument", "gain", "mode", "offset"]);
+    assert_eq!(ds.attr("gain")?.read_scalar::<f64>()?, 1.5);
+    assert_eq!(Meta::read_attrs(&ds)?, meta);
+
+    // writing again replaces the attributes, removing the ones set to `None`
+    let meta = Meta { gain: 2.0, offset: None, x: Some(0.5), ..meta };
+    meta.write_attrs(&ds)?;
+    assert!(!ds.attr_exists("offset"));
+    assert_eq!(Meta::read_attrs(&ds)?, meta);
+
+    ds.delete_attr("gain")?;
+    assert_err!(Meta::read_attrs(&ds), "Attribute \"gain\" not found");
+    ds.new_attr::<VarLenUnicode>().create("gain")?;
+    assert_err!(Meta::read_attrs(&ds), "Attribute \"gain\": ");
+    Ok(())
+}
+
+#[test]
+fn roundtrip_group() -> hdf5::Result<()> {
+    use hdf5::types::VarLenUnicode;
+    use hdf5::H5Group;
+    use ndarray::{arr1, Array1, Array2};
+
+    type Offsets = Array1<f64>;
+
+    #[derive(H5Group, Debug, PartialEq)]
+    struct Calibration {
+        #[hdf5(dataset)]
+        offsets: Offsets,
+        #[hdf5(rename = "Version")]
+        version: u32,
+    }
+
+    #[derive(H5Group, Debug, PartialEq)]
+    struct Bundle {
+        #[hdf5(chunk = (2, 3), deflate = 4)]
+        image: Array2<f32>,
+        mask: Option<Array2<bool>>,
+        label: VarLenUnicode,
+        scale: f64,
+        #[hdf5(group)]
+        calibration: Calibration,
+        #[hdf5(group, rename = "extra")]
+        extra_calibration: Option<Calibration>,
+    }
+
+    let file = new_in_memory_file()?;
+    let bundle = Bundle {
+        image: Array2::from_shape_fn((4, 6), |(i, j)| (i * 6 + j) as f32),
+        mask: None,
+        label: "frame".parse().unwrap(),
+        scale: 0.25,
+        calibration: Calibration { offsets: arr1(&[1., 2.]), version: 3 },
+        extra_calibration: None,
+    };
+    bundle.save(&file, "bundle")?;
+
+    let group = file.group("bundle")?;
+    let mut names = group.member_names()?;
+    names.sort();
+    assert_eq!(names, vec!["calibration", "image"]);
+    assert_eq!(group.dataset("image")?.chunk(), Some(vec![2, 3]));
+    assert_eq!(group.attr("scale")?.read_scalar::<f64>()?, 0.25);
+    assert_eq!(group.group("calibration")?.attr("Version")?.read_scalar::<u32>()?, 3);
+    assert_eq!(group.group("calibration")?.dataset("offsets")?.read_1d::<f64>()?, arr1(&[1., 2.]));
+    assert_eq!(Bundle::load(&file, "bundle")?, bundle);
+
+    let bundle = Bundle {
+        mask: Some(Array2::from_elem((4, 6), true)),
+        extra_calibration: Some(Calibration { offsets: arr1(&[]), version: 4 }),
+        ..bundle
+    };
+    bundle.save(&file, "other")?;
+    assert!(file.group("other")?.link_exists("extra"));
+    assert_eq!(Bundle::load(&file, "other")?, bundle);
+    assert!(bundle.save(&file, "other").is_err());
+    assert!(Bundle::load(&file, "missing").is_err());
+    Ok(())
+}